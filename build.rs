@@ -0,0 +1,36 @@
+extern crate gcc;
+
+use std::env;
+use std::process::Command;
+
+// native/debug_info.cpp wraps the C++ DIBuilder of LLVM for `-g`, so it's compiled with the flags of the LLVM that
+// llvm-sys links, which is found the same way. Without the debug-info feature, neither llvm-config nor a C++ compiler
+// is needed, and -g is rejected.
+fn main() {
+    println!("cargo:rerun-if-changed=native/debug_info.cpp");
+    println!("cargo:rerun-if-env-changed=LLVM_SYS_60_PREFIX");
+
+    if env::var("CARGO_FEATURE_DEBUG_INFO").is_err() {
+        return;
+    }
+
+    let llvm_config = match env::var("LLVM_SYS_60_PREFIX") {
+        Ok(prefix) => format!("{}/bin/llvm-config", prefix),
+        Err(_) => "llvm-config".to_string(),
+    };
+    let output = match Command::new(&llvm_config).arg("--cxxflags").output() {
+        Ok(output) if output.status.success() => output,
+        _ => panic!(
+            "Unable to run {}, which -g needs to compile native/debug_info.cpp. Install LLVM 6 with its headers, set \
+             LLVM_SYS_60_PREFIX to where it's installed, or build without -g: cargo build --no-default-features",
+            llvm_config
+        ),
+    };
+
+    let mut build = gcc::Build::new();
+    build.cpp(true).file("native/debug_info.cpp");
+    for flag in String::from_utf8(output.stdout).unwrap().split_whitespace() {
+        build.flag(flag);
+    }
+    build.compile("liblilit_debug_info.a");
+}
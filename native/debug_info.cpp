@@ -0,0 +1,130 @@
+// The debug info of the generated code for `-g`. The C API of LLVM 6 can create a compile unit but not the subprograms
+// and the variables, so this wraps the C++ DIBuilder. It's compiled by build.rs and linked into the compiler itself,
+// not into the Lilit program.
+#include <memory>
+#include <string>
+
+#include <llvm/BinaryFormat/Dwarf.h>
+#include <llvm/Config/llvm-config.h>
+#include <llvm/IR/DIBuilder.h>
+#include <llvm/IR/Function.h>
+#include <llvm/IR/IRBuilder.h>
+#include <llvm/IR/Instructions.h>
+#include <llvm/IR/Module.h>
+#include <llvm/Support/Path.h>
+
+using namespace llvm;
+
+// DIBuilder needs the module, which the first subprogram gives, so the compile unit is created with it.
+struct LilitDebugInfo {
+  std::string path;
+  Module* module;
+  std::unique_ptr<DIBuilder> builder;
+};
+
+static DIFile* create_file(LilitDebugInfo* info, const std::string& path) {
+  std::string dir = sys::path::parent_path(path).str();
+  return info->builder->createFile(sys::path::filename(path), dir.empty() ? "." : dir);
+}
+
+extern "C" {
+
+LilitDebugInfo* lilit_debug_info_create(const char* path) {
+  LilitDebugInfo* info = new LilitDebugInfo();
+  info->path = path;
+  info->module = nullptr;
+  return info;
+}
+
+// The params aren't described, so gdb shows the variables of a frame but not the signature of its function.
+void lilit_debug_info_create_subprogram(LilitDebugInfo* info, LLVMValueRef function_ref, const char* name, const char* path, unsigned line) {
+  Function* function = unwrap<Function>(function_ref);
+
+  if (!info->builder) {
+    info->module = function->getParent();
+    info->builder.reset(new DIBuilder(*info->module));
+    // Lilit has no DWARF language code, and C is the closest that gdb understands.
+    info->builder->createCompileUnit(dwarf::DW_LANG_C, create_file(info, info->path), "lilit", false, "", 0);
+  }
+
+  DIFile* file = create_file(info, path);
+  DISubroutineType* type = info->builder->createSubroutineType(info->builder->getOrCreateTypeArray({}));
+#if LLVM_VERSION_MAJOR < 8
+  DISubprogram* subprogram = info->builder->createFunction(
+    file, name, function->getName(), file, line, type, false, true, line, DINode::FlagPrototyped, false);
+#else
+  DISubprogram* subprogram = info->builder->createFunction(
+    file, name, function->getName(), file, line, type, line, DINode::FlagPrototyped, DISubprogram::SPFlagDefinition);
+#endif
+  function->setSubprogram(subprogram);
+}
+
+// Locates what the builder emits from now on, until the previous location, which is returned, is restored. An expr is
+// located while it's emitted, so an instruction gets the location of the innermost expr that emits it.
+DILocation* lilit_debug_info_set_location(LLVMBuilderRef builder_ref, unsigned line, unsigned col) {
+  IRBuilder<>* builder = unwrap(builder_ref);
+  DILocation* previous = builder->getCurrentDebugLocation().get();
+  DISubprogram* subprogram = builder->GetInsertBlock()->getParent()->getSubprogram();
+
+  // The functions that the compiler generates, e.g. the constructors, have no subprogram, so they aren't located.
+  if (subprogram == nullptr) {
+    builder->SetCurrentDebugLocation(DebugLoc());
+  } else {
+    builder->SetCurrentDebugLocation(DebugLoc(DILocation::get(builder->getContext(), line, col, subprogram)));
+  }
+
+  return previous;
+}
+
+void lilit_debug_info_restore_location(LLVMBuilderRef builder_ref, DILocation* previous) {
+  unwrap(builder_ref)->SetCurrentDebugLocation(DebugLoc(previous));
+}
+
+// The encoding is a DW_ATE_* constant, or 0 for a pointer, e.g. to the struct of an instance.
+void lilit_debug_info_declare_variable(
+  LilitDebugInfo* info,
+  LLVMValueRef alloca_ref,
+  const char* name,
+  const char* path,
+  unsigned line,
+  unsigned col,
+  const char* type_name,
+  unsigned size_in_bits,
+  unsigned encoding
+) {
+  AllocaInst* alloca = unwrap<AllocaInst>(alloca_ref);
+  DISubprogram* subprogram = alloca->getFunction()->getSubprogram();
+
+  if (subprogram == nullptr) {
+    return;
+  }
+
+  DIType* type = encoding == 0
+    ? static_cast<DIType*>(info->builder->createPointerType(nullptr, size_in_bits, 0, None, type_name))
+    : static_cast<DIType*>(info->builder->createBasicType(type_name, size_in_bits, encoding));
+  DILocalVariable* variable = info->builder->createAutoVariable(subprogram, name, create_file(info, path), line, type);
+  DILocation* location = DILocation::get(alloca->getContext(), line, col, subprogram);
+
+  info->builder->insertDeclare(alloca, variable, info->builder->createExpression(), location, alloca->getParent());
+}
+
+// The variables of a subprogram are a temporary node until it's finalized, which the verifier of the function rejects.
+void lilit_debug_info_finalize_subprogram(LilitDebugInfo* info, LLVMValueRef function_ref) {
+  DISubprogram* subprogram = unwrap<Function>(function_ref)->getSubprogram();
+
+  if (subprogram != nullptr) {
+    info->builder->finalizeSubprogram(subprogram);
+  }
+}
+
+void lilit_debug_info_finalize(LilitDebugInfo* info) {
+  if (info->builder) {
+    info->builder->finalize();
+    info->module->addModuleFlag(Module::Warning, "Debug Info Version", DEBUG_METADATA_VERSION);
+    info->module->addModuleFlag(Module::Warning, "Dwarf Version", 4);
+  }
+
+  delete info;
+}
+
+}
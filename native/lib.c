@@ -1,8 +1,17 @@
 #include <stdio.h>
+#include <stdlib.h>
+#include <stdint.h>
+#include <stdbool.h>
 #include <string.h>
 #include <gc.h>
 #include <unistd.h>
 #include <sys/wait.h>
+#ifdef __GLIBC__
+#include <execinfo.h>
+#endif
+
+#define LILIT_PANIC_EXIT_STATUS 101
+#define LILIT_PANIC_MAX_FRAMES 64
 
 struct Process {
   long pid;
@@ -11,18 +20,42 @@ struct Process {
   long err;
 };
 
+// Prints the message, where it happened in the Lilit source and the backtrace to stderr, and exits with 101, so a panic
+// is told apart from a crash, e.g. 139 for a segfault. The file is NULL when the runtime panics, which has no location
+// in the Lilit source. The backtrace is only printed with glibc, and its frames are named after the functions only when
+// the program is linked with -rdynamic.
+void lilit_panic(char* message, char* file, long line, long col) {
+  fflush(stdout);
+  fprintf(stderr, "panic: %s\n", message);
+  if (file != NULL) {
+    fprintf(stderr, "  at %s:%ld:%ld\n", file, line, col);
+  }
+
+#ifdef __GLIBC__
+  void* frames[LILIT_PANIC_MAX_FRAMES];
+  int size = backtrace(frames, LILIT_PANIC_MAX_FRAMES);
+  backtrace_symbols_fd(frames, size, STDERR_FILENO);
+#endif
+
+  exit(LILIT_PANIC_EXIT_STATUS);
+}
+
+// Returns NULL when the pipes or the process can't be created, so the invoke panics.
 struct Process* lilit_exec(char *cmd) {
    int* in = GC_malloc(sizeof(int) * 2);
    int* out = GC_malloc(sizeof(int) * 2);
    int* err = GC_malloc(sizeof(int) * 2);
 
-   pipe(in);
-   pipe(out);
-   pipe(err);
-
+  if (pipe(in) != 0 || pipe(out) != 0 || pipe(err) != 0) {
+    return NULL;
+  }
 
    int pid = fork();
 
+  if (pid < 0) {
+    return NULL;
+  }
+
   if (pid == 0) {
     close(in[1]);
     close(out[0]);
@@ -32,6 +65,7 @@ struct Process* lilit_exec(char *cmd) {
     dup2(err[1], STDERR_FILENO);
 
     execlp(cmd, cmd, (char*) NULL);
+    _exit(127); // The same status as a shell when the command isn't found.
   }
   close(in[0]);
   close(out[1]);
@@ -55,12 +89,157 @@ void lilit_write(int pipe, char c) {
   write(pipe, &c, 1);
 }
 
+int lilit_close(int fd) {
+  return close(fd);
+}
+
 long lilit_wait(int pid) {
   int exitCode;
   waitpid(pid, &exitCode, 0);
   return (long) WEXITSTATUS(exitCode);
 }
 
+static bool lilit_is_env_enabled(const char* name) {
+  char* value = getenv(name);
+  return value != NULL && strcmp(value, "1") == 0;
+}
+
+static void lilit_gc_print_stats(void) {
+  fprintf(
+    stderr,
+    "GC: %lu collections, %zu bytes in the heap, %zu bytes free, %zu bytes allocated in total\n",
+    (unsigned long) GC_get_gc_no(),
+    GC_get_heap_size(),
+    GC_get_free_bytes(),
+    GC_get_total_bytes()
+  );
+}
+
+// Initializes the GC before main runs. It's tuned with the env vars:
+//   LILIT_GC_INITIAL_HEAP_SIZE=<bytes> grows the heap upfront, so a program that allocates a lot collects less often.
+//   LILIT_GC_INCREMENTAL=1 collects in small steps, so the pauses are shorter.
+//   LILIT_GC_STATS=1 prints the number of collections and the heap size to stderr at exit.
+void lilit_gc_init(void) {
+  GC_INIT();
+
+  char* initial_heap_size = getenv("LILIT_GC_INITIAL_HEAP_SIZE");
+  if (initial_heap_size != NULL) {
+    size_t size = strtoull(initial_heap_size, NULL, 10);
+    size_t current_size = GC_get_heap_size();
+    if (size > current_size) {
+      GC_expand_hp(size - current_size);
+    }
+  }
+
+  if (lilit_is_env_enabled("LILIT_GC_INCREMENTAL")) {
+    GC_enable_incremental();
+  }
+
+  if (lilit_is_env_enabled("LILIT_GC_STATS")) {
+    atexit(lilit_gc_print_stats);
+  }
+}
+
+static void lilit_run_finalizer(void* obj, void* finalizer) {
+  ((void (*)(void*)) finalizer)(obj);
+}
+
+// Invokes the `finalize` method of obj's class when obj is collected.
+void lilit_gc_register_finalizer(void* obj, void* finalizer) {
+  GC_register_finalizer(obj, lilit_run_finalizer, finalizer, NULL, NULL);
+}
+
+// `using` has already finalized obj, so the GC doesn't finalize it again.
+void lilit_gc_unregister_finalizer(void* obj) {
+  GC_register_finalizer(obj, NULL, NULL, NULL, NULL);
+}
+
+char* lilit_string_concat(char* a, char* b) {
+  size_t a_len = strlen(a);
+  size_t b_len = strlen(b);
+  char* result = GC_malloc(a_len + b_len + 1);
+  memcpy(result, a, a_len);
+  memcpy(result + a_len, b, b_len + 1);
+  return result;
+}
+
+// String literals are read-only constants, which every use of the same text shares, so a native that modifies a string
+// is given a copy from here.
+char* lilit_string_copy(char* s) {
+  size_t len = strlen(s);
+  char* result = GC_malloc(len + 1);
+  memcpy(result, s, len + 1);
+  return result;
+}
+
+// Conversions between Int and the sized ints wrap around, e.g. converting 300 to a UInt8 gives 44.
+#define LILIT_INT_CONVERSIONS(name, type) \
+  type lilit_int_to_##name(int64_t value) { return (type) value; } \
+  int64_t lilit_##name##_to_int(type value) { return (int64_t) value; }
+
+LILIT_INT_CONVERSIONS(int8, int8_t)
+LILIT_INT_CONVERSIONS(int16, int16_t)
+LILIT_INT_CONVERSIONS(int32, int32_t)
+LILIT_INT_CONVERSIONS(int64, int64_t)
+LILIT_INT_CONVERSIONS(uint8, uint8_t)
+LILIT_INT_CONVERSIONS(uint16, uint16_t)
+LILIT_INT_CONVERSIONS(uint32, uint32_t)
+LILIT_INT_CONVERSIONS(uint64, uint64_t)
+LILIT_INT_CONVERSIONS(byte, uint8_t)
+LILIT_INT_CONVERSIONS(size, ssize_t)
+LILIT_INT_CONVERSIONS(usize, size_t)
+
+// Both a zero divisor and INT64_MIN / -1 raise SIGFPE on x86, so they panic instead. The compiler already checks them
+// at the invoke, which has the location in the Lilit source, so these only catch what isn't compiled from Lilit.
+int64_t lilit_int_div(int64_t a, int64_t b) {
+  if (b == 0) {
+    lilit_panic("Division by zero", NULL, 0, 0);
+  }
+  if (a == INT64_MIN && b == -1) {
+    lilit_panic("Division overflow", NULL, 0, 0);
+  }
+  return a / b;
+}
+
+// The remainder of INT64_MIN % -1 is 0, but computing it traps like the division.
+int64_t lilit_int_mod(int64_t a, int64_t b) {
+  if (b == 0) {
+    lilit_panic("Division by zero", NULL, 0, 0);
+  }
+  if (b == -1) {
+    return 0;
+  }
+  return a % b;
+}
+
+bool lilit_int_eq(int64_t a, int64_t b) {
+  return a == b;
+}
+
+bool lilit_char_eq(char a, char b) {
+  return a == b;
+}
+
+bool lilit_string_eq(char* a, char* b) {
+  return strcmp(a, b) == 0;
+}
+
+double lilit_float_add(double a, double b) {
+  return a + b;
+}
+
+double lilit_float_sub(double a, double b) {
+  return a - b;
+}
+
+double lilit_float_mul(double a, double b) {
+  return a * b;
+}
+
+double lilit_float_div(double a, double b) {
+  return a / b;
+}
+
 struct Test {
   long pid;
   long in;
@@ -72,10 +251,3 @@ struct Test* test_call() {
   t->in = 37;
   return t;
 }
-
-int GC_finalizer_count = 0;
-
-void GC_finalizer(char* a, char* b) {
-  GC_finalizer_count++;
-  printf("GC freed count: %d\n", GC_finalizer_count);
-}
\ No newline at end of file
@@ -4,6 +4,6 @@ set -x #echo on
 cargo run examples/bash.lilit \
   && clang -S -emit-llvm ~/projects/bdwgc/.libs/libgc.so -I ~/projects/bdwgc/include/ -o native/lib.ll native/lib.c \
   && llc-6.0 -filetype=obj native/lib.ll \
-  && cc native/lib.o output/main.o ~/projects/bdwgc/.libs/libgc.so -I ~/projects/bdwgc/include/ -o main -no-pie \
+  && cc native/lib.o output/main.o ~/projects/bdwgc/.libs/libgc.so -I ~/projects/bdwgc/include/ -o main -no-pie -rdynamic \
   && ./main
 echo $?
@@ -1,19 +1,72 @@
-use parse::tree::{Class, ParamParent};
+use parse::tree::{Class, ParamParent, Param, Expr};
 use analyse::def::{method, params};
+use analyse::expr;
 use analyse::scope::Scope;
+use analyse::error::Error;
 use std::ops::Deref;
 
 pub fn apply<'def>(
     class: &mut Class<'def>,
     scope: &mut Scope<'def>,
+    errors: &mut Vec<Error<'def>>,
 ) {
     scope.enter_class(class);
 
+    // The rest of the class refers to its members, so it isn't analysed when their types are unknown.
     let parent = class as *const Class<'def>;
-    params::apply(&mut class.params, ParamParent::Class(parent), scope);
+    let result = params::apply(&mut class.params, ParamParent::Class(parent), scope)
+        .and_then(|_| params::apply(&mut class.fields, ParamParent::Class(parent), scope));
+    if let Err(error) = result {
+        errors.push(error);
+        scope.leave();
+        return;
+    }
+
+    for field in &mut class.fields {
+        field.index += class.params.len();
+
+        if let Some(value) = &mut field.value_opt {
+            let depth = scope.levels.len();
+            if let Err(error) = expr::apply_value(value, scope) {
+                errors.push(error);
+            }
+            scope.levels.truncate(depth);
+        }
+    }
 
     for m in &mut class.methods {
-        method::apply(m, Some(parent), scope);
+        if let Err(error) = method::apply(m, Some(parent), scope) {
+            errors.push(error);
+        }
     }
     scope.leave();
+
+    for field in &class.fields {
+        if !field.is_mutable && field.value_opt.is_none() && !is_assigned_by_initializer(class, field) {
+            let name = field.name.unwrap();
+            errors.push(Error::new(
+                name,
+                format!(
+                    "The immutable field {} of the class {} must be assigned by `self.{} = ...` in `def init(): Void`",
+                    name.fragment,
+                    class.name.fragment,
+                    name.fragment
+                )
+            ));
+        }
+    }
+}
+
+// The assignment must be a top-level expr of the initializer, so it always runs.
+fn is_assigned_by_initializer<'def>(class: &Class<'def>, field: &Param<'def>) -> bool {
+    match class.find_initializer() {
+        Some(initializer) => initializer.exprs.iter().any(|e| match e {
+            Expr::MemberAssignment(m) => m.member.param_def == Some(field as *const Param<'def>) && match m.member.parent {
+                Expr::SelfInstance(_) => true,
+                _ => false,
+            },
+            _ => false,
+        }),
+        None => false,
+    }
 }
@@ -2,35 +2,67 @@ use parse::tree::{Method, ParamParent, Class, Param, Type};
 use analyse::{expr, tpe};
 use analyse::scope::Scope;
 use analyse::def::params;
+use analyse::error::{Error, AnalyseResult};
 use std::cell::Cell;
 
 pub fn apply<'def>(
     method: &mut Method<'def>,
     parent_class: Option<*const Class<'def>>,
     scope: &mut Scope<'def>
-) {
+) -> AnalyseResult<'def> {
+    let depth = scope.levels.len();
     scope.enter_method(method);
+    let result = apply_method(method, parent_class, scope);
+
+    // An error skips leaving the levels that it's in, e.g. of a `using` block, so they're all left here.
+    scope.levels.truncate(depth);
+    result
+}
+
+fn apply_method<'def>(
+    method: &mut Method<'def>,
+    parent_class: Option<*const Class<'def>>,
+    scope: &mut Scope<'def>
+) -> AnalyseResult<'def> {
+    method.parent_class = parent_class;
 
     if let Some(parent_class) = parent_class {
-        method.params.insert(0, Param {
-            name: None,
-            tpe: Type {
-                span: None,
-                class_def: Some(parent_class),
-            },
-            is_varargs: false,
-            index: 0,
-            parent: Some(ParamParent::Method(method)),
-            llvm: Cell::new(None)
-        })
+        let class = unsafe { &*parent_class };
+
+        // A secondary constructor builds the instance by itself, and a static method isn't bound to any instance.
+        // Therefore, they don't take the receiver.
+        if !class.is_constructor(method) && !method.is_static {
+            if method.name.fragment == "init" && !method.params.is_empty() {
+                return Err(Error::new(method.name, format!("The initializer of the class {} can't take params. Declare a secondary constructor `def init(...): {}` instead", class.name.fragment, class.name.fragment)));
+            }
+
+            if method.name.fragment == "finalize" && (!method.params.is_empty() || method.return_type.span.map(|s| s.fragment) != Some("Void")) {
+                return Err(Error::new(method.name, format!("The finalizer of the class {} must be declared as `def finalize(): Void`", class.name.fragment)));
+            }
+
+            method.params.insert(0, Param {
+                name: None,
+                tpe: Type {
+                    span: None,
+                    class_def: Some(parent_class),
+                },
+                is_varargs: false,
+                is_mutable: false,
+                value_opt: None,
+                index: 0,
+                parent: Some(ParamParent::Method(method)),
+                llvm: Cell::new(None)
+            })
+        }
     }
 
     let parent = ParamParent::Method(method);
-    params::apply(&mut method.params, parent, scope);
-    tpe::apply(&mut method.return_type, scope);
+    params::apply(&mut method.params, parent, scope)?;
+    tpe::apply(&mut method.return_type, scope)?;
 
     for e in &mut method.exprs {
-        expr::apply(e, scope);
+        expr::apply(e, scope)?;
     }
-    scope.leave();
+
+    Ok(())
 }
@@ -0,0 +1,24 @@
+use std::fmt;
+
+use tokenize::span::Span;
+
+// An error in the code, located at the span that causes it, e.g. the name of an identifier that isn't declared.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Error<'def> {
+    pub span: Span<'def>,
+    pub message: String,
+}
+
+pub type AnalyseResult<'def> = Result<(), Error<'def>>;
+
+impl <'def> Error<'def> {
+    pub fn new(span: Span<'def>, message: String) -> Error<'def> {
+        Error { span, message }
+    }
+}
+
+impl <'def> fmt::Display for Error<'def> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at line {}, column {}", self.message, self.span.line, self.span.col)
+    }
+}
@@ -1,16 +1,18 @@
 use parse::tree::{Identifier, Param, Assignment};
 use analyse::scope::Scope;
 use analyse::expr;
-use analyse::tpe::GetType;
+use analyse::tpe;
+use analyse::error::AnalyseResult;
 
 pub fn apply<'def>(
     assignment: &mut Assignment<'def>,
     scope: &mut Scope<'def>,
-) {
-    expr::apply(&mut assignment.expr, scope);
-    assignment.tpe = Some(assignment.expr.get_type(scope));
+) -> AnalyseResult<'def> {
+    expr::apply_value(&mut assignment.expr, scope)?;
+    assignment.tpe = Some(tpe::require_type(&assignment.expr, assignment.name, scope)?);
 
     scope.add_var(assignment);
+    Ok(())
 }
 
 #[cfg(test)]
@@ -0,0 +1,26 @@
+use parse::tree::{Boolean, NewInstance, NativeBool, Expr};
+use analyse::scope::Scope;
+use analyse::error::AnalyseResult;
+
+pub fn apply<'def>(
+    boolean: &mut Boolean<'def>,
+    scope: &mut Scope<'def>,
+) -> AnalyseResult<'def> {
+    boolean.instance = Some(Box::new(NewInstance {
+        name_opt: None,
+        args: vec![
+            Expr::NewInstance(Box::new(NewInstance {
+                name_opt: None,
+                args: vec![
+                    Expr::NativeBool(Box::new(NativeBool { value: boolean.span.fragment == "true" }))
+                ],
+                class_def: Some(scope.require_class("Native__Bool", boolean.span)?),
+                constructor_def: None
+            })),
+        ],
+        class_def: Some(scope.require_class("Boolean", boolean.span)?),
+        constructor_def: None
+    }));
+
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use parse::tree::{Float, NewInstance, NativeFloat, Expr};
+use analyse::scope::Scope;
+use tokenize::span::Span;
+use analyse::error::{Error, AnalyseResult};
+
+pub fn apply<'def>(
+    float: &mut Float<'def>,
+    scope: &mut Scope<'def>,
+) -> AnalyseResult<'def> {
+    float.instance = Some(Box::new(NewInstance {
+        name_opt: None,
+        args: vec![
+            Expr::NewInstance(Box::new(NewInstance {
+                name_opt: None,
+                args: vec![
+                    Expr::NativeFloat(Box::new(NativeFloat { value: decode(float.span)? }))
+                ],
+                class_def: Some(scope.require_class("Native__Float", float.span)?),
+                constructor_def: None
+            })),
+        ],
+        class_def: Some(scope.require_class("Float", float.span)?),
+        constructor_def: None
+    }));
+
+    Ok(())
+}
+
+fn decode<'def>(span: Span<'def>) -> Result<f64, Error<'def>> {
+    let fragment = span.fragment;
+    let digits = fragment.replace('_', "");
+    let value = if digits.starts_with("0x") || digits.starts_with("0X") {
+        decode_hex(&digits[2..])
+    } else {
+        digits.parse::<f64>().ok()
+    };
+
+    match value {
+        Some(value) if value.is_finite() => Ok(value),
+        _ => Err(Error::new(span, format!("The float literal {} is invalid or out of range", fragment))),
+    }
+}
+
+// Decodes a hex float without its `0x` prefix, e.g. `1.8p3` is 1.5 * 2^3.
+fn decode_hex(fragment: &str) -> Option<f64> {
+    let p = fragment.find(|c| c == 'p' || c == 'P')?;
+    let exponent = fragment[(p + 1)..].parse::<i32>().ok()?;
+
+    let mut mantissa = 0f64;
+    let mut fraction_digits = 0;
+    let mut after_dot = false;
+
+    for c in fragment[..p].chars() {
+        if c == '.' {
+            after_dot = true;
+            continue;
+        }
+
+        mantissa = mantissa * 16.0 + c.to_digit(16)? as f64;
+        if after_dot {
+            fraction_digits += 1;
+        }
+    }
+
+    Some(mantissa * 2f64.powi(exponent - 4 * fraction_digits))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::{Deref, DerefMut};
+
+    use index::build;
+    use parse;
+    use parse::tree::{Expr, Float, NewInstance, NativeFloat};
+    use test_common::span2;
+    use analyse::apply;
+    use analyse::expr::float::decode_hex;
+
+    #[test]
+    fn test_simple() {
+        let content = r#"
+class Native__Float
+end
+
+class Float(underlying: Native__Float)
+end
+
+def main: Float
+  1.5e1
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+
+        assert_eq!(
+            root.find_method("main").exprs.get(0).unwrap(),
+            &Expr::Float(Box::new(Float {
+                span: span2(8, 3, "1.5e1", file.deref()),
+                instance: Some(Box::new(
+                    NewInstance {
+                        name_opt: None,
+                        args: vec![
+                            Expr::NewInstance(Box::new(NewInstance {
+                                name_opt: None,
+                                args: vec![
+                                    Expr::NativeFloat(Box::new(NativeFloat { value: 15.0 }))
+                                ],
+                                class_def: Some(root.find_class("Native__Float")),
+                                constructor_def: None
+                            })),
+                        ],
+                        class_def: Some(root.find_class("Float")),
+                        constructor_def: None
+                    }
+                ))
+            }))
+        )
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(decode_hex("2p+3"), Some(16.0));
+        assert_eq!(decode_hex("2p-3"), Some(0.25));
+        assert_eq!(decode_hex("1.8p1"), Some(3.0));
+        assert_eq!(decode_hex(".1p4"), Some(1.0));
+    }
+}
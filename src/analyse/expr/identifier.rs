@@ -1,30 +1,50 @@
 use parse::tree::{Identifier, Param, IdentifierSource, ParamParent, MemberAccess, Expr};
 use analyse::scope::Scope;
+use analyse::expr::self_instance;
+use analyse::error::{Error, AnalyseResult};
 use std::cell::{Cell, RefCell};
 
 pub fn apply<'def>(
     identifier: &mut Identifier<'def>,
     scope: &mut Scope<'def>,
-) {
-    let source = scope.find_identifier(identifier.name.unwrap().fragment).unwrap();
+) -> AnalyseResult<'def> {
+    let name = identifier.name.unwrap();
+    let source = match scope.find_identifier(name.fragment) {
+        Some(source) => source,
+        None => return Err(Error::new(name, format!("Unable to find the identifier {}", name.fragment))),
+    };
 
     if let IdentifierSource::Param(param) = source {
         let param = unsafe { &* param };
         if let Some(ParamParent::Class(class)) = param.parent {
-            let parent_method = scope.find_parent_method();
+            // The default value of a field is evaluated before the instance exists.
+            if scope.find_parent_method_opt().is_none() {
+                return Err(Error::new(name, format!("Unable to read the member {} in the default value of a field", name.fragment)));
+            }
+
+            if scope.find_parent_method().is_static {
+                return Err(Error::new(name, format!("Unable to read the member {} in a static method", name.fragment)));
+            }
+
+            // A bare member is read from the receiver, which a secondary constructor doesn't have.
+            let receiver = match self_instance::find_receiver(scope) {
+                Some(receiver) => receiver,
+                None => return Err(Error::new(name, format!("Unable to read the member {} without an instance", name.fragment))),
+            };
             identifier.source = Some(IdentifierSource::ClassParam(Box::new(MemberAccess {
                 parent: Expr::Identifier(Box::new(Identifier {
                     name: None,
-                    source: Some(IdentifierSource::Param(parent_method.params.get(0).unwrap()))
+                    source: Some(IdentifierSource::Param(receiver))
                 })),
                 name: None,
                 param_def: Some(param),
             })));
-            return;
+            return Ok(());
         }
     }
 
     identifier.source = Some(source);
+    Ok(())
 }
 
 #[cfg(test)]
@@ -73,4 +93,59 @@ end
             ]
         )
     }
+
+    #[test]
+    #[should_panic(expected = "Unable to read the member b without an instance at line 6, column 13")]
+    fn test_class_param_in_secondary_constructor() {
+        let content = r#"
+class Int
+end
+
+class Test(a: Int, b: Int)
+  def init(a: Int): Test
+    Test(a, b)
+  end
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unable to read the member a in the default value of a field at line 5, column 16")]
+    fn test_class_param_in_field_value() {
+        let content = r#"
+class Int
+end
+
+class Test(a: Int)
+  var b: Int = a
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unable to read the member a in a static method at line 6, column 5")]
+    fn test_class_param_in_static_method() {
+        let content = r#"
+class Int
+end
+
+class Test(a: Int)
+  static def build(): Int
+    a
+  end
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+    }
 }
@@ -1,24 +1,51 @@
 use parse::tree::{Int, NewInstance, NativeInt, Expr};
 use analyse::scope::Scope;
-use std::cell::Cell;
+use tokenize::span::Span;
+use analyse::error::{Error, AnalyseResult};
 
 pub fn apply<'def>(
     int: &mut Int<'def>,
     scope: &mut Scope<'def>,
-) {
+) -> AnalyseResult<'def> {
     int.instance = Some(Box::new(NewInstance {
         name_opt: None,
         args: vec![
             Expr::NewInstance(Box::new(NewInstance {
                 name_opt: None,
                 args: vec![
-                    Expr::NativeInt(Box::new(NativeInt { value: int.span.fragment.parse::<i64>().unwrap() }))
+                    Expr::NativeInt(Box::new(NativeInt { value: decode(int.span)? }))
                 ],
-                class_def: Some(scope.find_class("Native__Int").unwrap().parse)
+                class_def: Some(scope.require_class("Native__Int", int.span)?),
+                constructor_def: None
             })),
         ],
-        class_def: Some(scope.find_class("Int").unwrap().parse)
+        class_def: Some(scope.require_class("Int", int.span)?),
+        constructor_def: None
     }));
+
+    Ok(())
+}
+
+// Hex and binary literals may use all 64 bits, e.g. `0xFFFF_FFFF_FFFF_FFFF` is -1. Decimal literals must fit in an i64.
+fn decode<'def>(span: Span<'def>) -> Result<i64, Error<'def>> {
+    let fragment = span.fragment;
+    let (digits, radix) = match fragment.get(..2) {
+        Some("0x") | Some("0X") => (&fragment[2..], 16),
+        Some("0b") | Some("0B") => (&fragment[2..], 2),
+        _ => (fragment, 10),
+    };
+    let digits = digits.replace('_', "");
+
+    let value = if radix == 10 {
+        digits.parse::<i64>().ok()
+    } else {
+        u64::from_str_radix(&digits, radix).ok().map(|v| v as i64)
+    };
+
+    match value {
+        Some(value) => Ok(value),
+        None => Err(Error::new(span, format!("The integer literal {} is invalid or doesn't fit in 64 bits", fragment))),
+    }
 }
 
 #[cfg(test)]
@@ -66,13 +93,61 @@ end
                               args: vec![
                                   Expr::NativeInt(Box::new(NativeInt { value: 1 }))
                               ],
-                              class_def: Some(root.find_class("Native__Int"))
+                              class_def: Some(root.find_class("Native__Int")),
+                              constructor_def: None
                           })),
                       ],
-                      class_def: Some(root.find_class("Int"))
+                      class_def: Some(root.find_class("Int")),
+                      constructor_def: None
                   }
                 ))
             }))
         )
     }
+
+    fn main_int(literal: &str) -> i64 {
+        let content = format!(r#"
+class Void
+end
+
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+def main: Void
+  {}
+end
+        "#, literal);
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+
+        let int = unwrap!(Expr::Int, root.find_method("main").exprs.get(0).unwrap());
+        let native = unwrap!(Expr::NewInstance, int.instance.as_ref().unwrap().args.get(0).unwrap());
+        unwrap!(Expr::NativeInt, native.args.get(0).unwrap()).value
+    }
+
+    #[test]
+    fn test_radix() {
+        assert_eq!(main_int("0xFF"), 255);
+        assert_eq!(main_int("0b1010_0001"), 0b1010_0001);
+        assert_eq!(main_int("1_000_000"), 1_000_000);
+        assert_eq!(main_int("9223372036854775807"), i64::max_value());
+        assert_eq!(main_int("0xFFFF_FFFF_FFFF_FFFF"), -1);
+    }
+
+    #[test]
+    #[should_panic(expected = "The integer literal 9223372036854775808 is invalid or doesn't fit in 64 bits at line 11, column 3")]
+    fn test_decimal_overflow() {
+        main_int("9223372036854775808");
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit in 64 bits")]
+    fn test_hex_overflow() {
+        main_int("0x1_0000_0000_0000_0000");
+    }
 }
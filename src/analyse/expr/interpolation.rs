@@ -0,0 +1,138 @@
+use parse::tree::{Interpolation, InterpolationPart, Expr, Invoke, NativeInt, Class};
+use analyse::scope::Scope;
+use analyse::expr;
+use analyse::expr::literal_string;
+use analyse::tpe;
+use analyse::error::{Error, AnalyseResult};
+use tokenize::span::Span;
+
+pub fn apply<'def>(
+    interpolation: &mut Interpolation<'def>,
+    scope: &mut Scope<'def>,
+) -> AnalyseResult<'def> {
+    let string_class = scope.require_class("String", interpolation.span)?;
+
+    for part in &mut interpolation.parts {
+        match part {
+            InterpolationPart::Text(text) => {
+                let value = literal_string::unescape(text.span)?;
+                text.instance = Some(Box::new(literal_string::build_instance(value, text.span, scope)?));
+            },
+            InterpolationPart::Expr(e) => {
+                expr::apply_value(e, scope)?;
+                // An error is located at the expr, e.g. the name of a variable, instead of the whole interpolation.
+                let span = e.get_span().unwrap_or(interpolation.span);
+
+                // The type refers to the class definition, which outlives the expression being replaced below.
+                let class = unsafe { &*(tpe::require_type(e, span, scope)? as *const Class<'def>) };
+                if class as *const Class<'def> == string_class {
+                    continue;
+                }
+
+                let to_s = match class.find_method_opt("to_s") {
+                    Some(to_s) if !to_s.is_static && to_s.return_type.class_def == Some(string_class) => to_s,
+                    _ => return Err(Error::new(
+                        span,
+                        format!("The method to_s of the class {} must be an instance method that returns String", class.name.fragment)
+                    )),
+                };
+
+                let invoker = std::mem::replace(e, Expr::NativeInt(Box::new(NativeInt { value: 0 })));
+                *e = Expr::Invoke(Box::new(Invoke {
+                    invoker_opt: Some(invoker),
+                    name: Span { line: span.line, col: span.col, fragment: "to_s", file: span.file },
+                    args: vec![],
+                    method_def: Some(to_s),
+                    static_invoker_opt: None,
+                }));
+            },
+        }
+    }
+
+    interpolation.class_def = Some(string_class);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use index;
+    use parse;
+    use analyse::{apply, check};
+    use parse::tree::{Expr, InterpolationPart, NativeString};
+    use std::ops::{Deref, DerefMut};
+
+    #[test]
+    fn test_simple() {
+        let content = r#"
+class Void
+end
+
+class Native__String
+end
+
+class String(underlying: Native__String)
+end
+
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+  def to_s(): String
+    "int"
+  end
+end
+
+def main(): String
+  "a#{1}\n#{"b"}"
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+
+        let interpolation = unwrap!(Expr::Interpolation, root.find_method("main").exprs.get(0).unwrap());
+        assert_eq!(interpolation.class_def, Some(root.find_class("String") as *const _));
+        assert_eq!(interpolation.parts.len(), 4);
+
+        let text = unwrap!(InterpolationPart::Text, interpolation.parts.get(2).unwrap());
+        let native = unwrap!(Expr::NewInstance, text.instance.as_ref().unwrap().args.get(0).unwrap());
+        assert_eq!(native.args, vec![Expr::NativeString(Box::new(NativeString { value: "\n".to_string() }))]);
+
+        let to_s = unwrap!(Expr::Invoke, unwrap!(InterpolationPart::Expr, interpolation.parts.get(1).unwrap()));
+        assert_eq!(to_s.name.fragment, "to_s");
+        assert_eq!(to_s.method_def, Some(root.find_class("Int").find_method("to_s") as *const _));
+
+        let string = unwrap!(InterpolationPart::Expr, interpolation.parts.get(3).unwrap());
+        assert_eq!(unwrap!(Expr::String, string).span.fragment, "\"b\"");
+    }
+
+    #[test]
+    fn test_without_to_s() {
+        let content = r#"
+class Native__String
+end
+
+class String(underlying: Native__String)
+end
+
+class Counter
+end
+
+def main(): String
+  counter = Counter()
+  "count: #{counter}"
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        let errors = check(&mut [file.deref_mut()], &root);
+
+        assert_eq!(
+            errors.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+            vec!["The method to_s of the class Counter must be an instance method that returns String at line 12, column 13"]
+        );
+        assert_eq!(errors[0].span.fragment, "counter");
+    }
+}
@@ -1,32 +1,95 @@
-use parse::tree::{Expr, Invoke, Class, Method};
+use parse::tree::{Expr, Invoke, Class, Method, SelfInstance};
 use analyse::scope::Scope;
 use analyse::expr;
+use analyse::expr::self_instance;
+use tokenize::span::Span;
+use analyse::tpe;
 use analyse::tpe::GetType;
+use analyse::error::{Error, AnalyseResult};
 
 pub fn apply<'def>(
     invoke: &mut Invoke<'def>,
     scope: &mut Scope<'def>,
-) {
+) -> AnalyseResult<'def> {
     for arg in &mut invoke.args {
-        expr::apply(arg, scope);
+        expr::apply_value(arg, scope)?;
+    }
+
+    let name = invoke.name;
+    if invoke.is_panic() && scope.find_method(name.fragment).is_none() {
+        let is_string = invoke.args.len() == 1 && invoke.args[0].find_type(scope).map_or(false, |c| c.name.fragment == "String");
+        if !is_string {
+            return Err(Error::new(name, "The built-in panic expects a single String message".to_string()));
+        }
+        return Ok(());
     }
 
     invoke.method_def = Some(
-        match &mut invoke.invoker_opt {
-            Some(parent) => {
-                expr::apply(parent, scope);
-                parent.get_type(scope).find_method(invoke.name.fragment)
+        match (&mut invoke.invoker_opt, invoke.static_invoker_opt) {
+            (Some(parent), _) => {
+                expr::apply_value(parent, scope)?;
+                let method = find_method(tpe::require_type(parent, name, scope)?, name)?;
+                if method.is_static {
+                    return Err(Error::new(name, format!("Unable to invoke the static method {} on an instance", name.fragment)));
+                }
+                method
+            },
+            (None, Some(class_name)) => {
+                let class = unsafe { &*scope.require_class(class_name.fragment, class_name)? };
+                let method = find_method(class, name)?;
+                if !method.is_static {
+                    return Err(Error::new(name, format!("The method {} of the class {} isn't static", name.fragment, class_name.fragment)));
+                }
+                method
+            },
+            (None, None) => match scope.find_method(name.fragment) {
+                Some(method) => method.parse,
+                None => return Err(Error::new(name, format!("Unable to find the method {}", name.fragment))),
             },
-            None => scope.find_method(invoke.name.fragment).unwrap().parse,
         }
     );
+
+    if invoke.invoker_opt.is_none() && is_instance_method(invoke.method_def.unwrap(), scope) {
+        match self_instance::find_receiver(scope) {
+            Some(receiver) => invoke.invoker_opt = Some(Expr::SelfInstance(Box::new(SelfInstance {
+                span: None,
+                param_def: Some(receiver),
+            }))),
+            None => return Err(Error::new(name, format!("Unable to invoke the instance method {} without an instance", name.fragment))),
+        }
+    }
+
+    Ok(())
+}
+
+fn find_method<'a, 'def>(
+    class: &'a Class<'def>,
+    name: Span<'def>,
+) -> Result<&'a Method<'def>, Error<'def>> {
+    match class.find_method_opt(name.fragment) {
+        Some(method) => Ok(method),
+        None => Err(Error::new(name, format!("Unable to find the method {} in the class {}", name.fragment, class.name.fragment))),
+    }
+}
+
+fn is_instance_method<'def>(
+    method: *const Method<'def>,
+    scope: &Scope<'def>,
+) -> bool {
+    match scope.find_parent_method_opt().and_then(|m| m.parent_class) {
+        Some(class) => {
+            let class = unsafe { &*class };
+            class.methods.iter().any(|m| m as *const Method<'def> == method && !class.is_constructor(m) && !m.is_static)
+        },
+        None => false,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use index;
     use parse;
-    use analyse::apply;
+    use analyse::{apply, check};
     use parse::tree::{Method, Type, Expr, MemberAccess, NewInstance, LiteralString, NativeString, Invoke};
     use test_common::span2;
     use std::cell::{Cell, RefCell};
@@ -55,13 +118,169 @@ end
                     invoker_opt: Some(Expr::NewInstance(Box::new(NewInstance {
                         name_opt: Some(span2(7, 3, "Test", file.deref())),
                         args: vec![],
-                        class_def: Some(root.find_class("Test"))
+                        class_def: Some(root.find_class("Test")),
+                        constructor_def: None
                     }))),
                     name: span2(7, 10, "run", file.deref()),
                     args: vec![],
                     method_def: Some(root.find_class("Test").find_method("run")),
+                    static_invoker_opt: None,
                 }))
             ]
         )
     }
+
+    #[test]
+    fn test_static_method() {
+        let content = r#"
+class Test
+  static def build(): Test
+    Test()
+  end
+
+  def run(): Test
+    build()
+  end
+end
+
+def main(): Test
+  Test.build()
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+
+        let test_class = root.find_class("Test");
+        let build_method = test_class.find_method("build");
+        assert_eq!(build_method.params, vec![]);
+        assert_eq!(
+            root.find_method("main").exprs,
+            vec![
+                Expr::Invoke(Box::new(Invoke {
+                    invoker_opt: None,
+                    name: span2(12, 8, "build", file.deref()),
+                    args: vec![],
+                    method_def: Some(build_method),
+                    static_invoker_opt: Some(span2(12, 3, "Test", file.deref())),
+                }))
+            ]
+        );
+        assert_eq!(
+            test_class.find_method("run").exprs,
+            vec![
+                Expr::Invoke(Box::new(Invoke {
+                    invoker_opt: None,
+                    name: span2(7, 5, "build", file.deref()),
+                    args: vec![],
+                    method_def: Some(build_method),
+                    static_invoker_opt: None,
+                }))
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The method run of the class Test isn't static")]
+    fn test_static_invoke_on_instance_method() {
+        let content = r#"
+class Test
+  def run(): Test
+  end
+end
+
+def main(): Test
+  Test.run()
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+    }
+
+    #[test]
+    fn test_panic() {
+        let content = r#"
+class Native__String
+end
+
+class String(underlying: Native__String)
+end
+
+class Void
+end
+
+def main(): Void
+  panic("Unreachable")
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+
+        let panic = unwrap!(Expr::Invoke, &root.find_method("main").exprs[0]);
+        assert!(panic.is_panic());
+        assert_eq!(panic.method_def, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The built-in panic expects a single String message at line 5, column 3")]
+    fn test_panic_without_message() {
+        let content = r#"
+class Void
+end
+
+def main(): Void
+  panic()
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+    }
+
+    #[test]
+    fn test_panic_as_value() {
+        let content = r#"
+class Native__String
+end
+
+class String(underlying: Native__String)
+end
+
+class Void
+end
+
+def log(text: String): Void
+end
+
+def assign(): Void
+  text = panic("Unable to assign")
+end
+
+def pass(): Void
+  log(panic("Unable to pass"))
+end
+
+def last(): String
+  panic("Unable to return")
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        let errors = check(&mut [file.deref_mut()], &root);
+
+        assert_eq!(
+            errors.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+            vec![
+                "Unable to use panic as a value because it never returns at line 14, column 10",
+                "Unable to use panic as a value because it never returns at line 18, column 7",
+            ]
+        );
+    }
 }
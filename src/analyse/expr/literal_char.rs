@@ -1,25 +1,50 @@
 use parse::tree::{NewInstance, Expr, NativeChar, Char};
 use analyse::scope::Scope;
-use std::cell::Cell;
-use tokenize::span::CharAt;
+use analyse::expr::literal_string;
+use tokenize::span::Span;
+use analyse::error::{Error, AnalyseResult};
 
 pub fn apply<'def>(
     char: &mut Char<'def>,
     scope: &mut Scope<'def>,
-) {
+) -> AnalyseResult<'def> {
+    let value = decode(char.span)?;
     char.instance = Some(Box::new(NewInstance {
         name_opt: None,
         args: vec![
             Expr::NewInstance(Box::new(NewInstance {
                 name_opt: None,
                 args: vec![
-                    Expr::NativeChar(Box::new(NativeChar { value: char.span.fragment.char_at(1) }))
+                    Expr::NativeChar(Box::new(NativeChar { value }))
                 ],
-                class_def: Some(scope.find_class("Native__Char").unwrap().parse)
+                class_def: Some(scope.require_class("Native__Char", char.span)?),
+                constructor_def: None
             })),
         ],
-        class_def: Some(scope.find_class("Char").unwrap().parse)
+        class_def: Some(scope.require_class("Char", char.span)?),
+        constructor_def: None
     }));
+
+    Ok(())
+}
+
+// Decodes the literal including its quotes. Native__Char is a single byte, so only ASCII is accepted.
+fn decode<'def>(span: Span<'def>) -> Result<char, Error<'def>> {
+    let fragment = span.fragment;
+
+    if fragment.len() < 2 || !fragment.ends_with('\'') {
+        return Err(Error::new(span, format!("The char literal {} isn't terminated", fragment)));
+    }
+
+    let raw = Span { col: span.col + 1, fragment: &fragment[1..(fragment.len() - 1)], ..span };
+    let value = literal_string::unescape(raw)?;
+    let mut chars = value.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii() => Ok(c),
+        (Some(_), None) => Err(Error::new(span, format!("The char literal {} isn't ASCII. Use a String instead", fragment))),
+        _ => Err(Error::new(span, format!("The char literal {} must contain exactly one char", fragment))),
+    }
 }
 
 #[cfg(test)]
@@ -64,13 +89,74 @@ end
                                 args: vec![
                                     Expr::NativeChar(Box::new(NativeChar { value: 'a' }))
                                 ],
-                                class_def: Some(root.find_class("Native__Char"))
+                                class_def: Some(root.find_class("Native__Char")),
+                                constructor_def: None
                             })),
                         ],
-                        class_def: Some(root.find_class("Char"))
+                        class_def: Some(root.find_class("Char")),
+                        constructor_def: None
                     }
                 ))
             }))
         )
     }
+
+    fn main_char(content: &str) -> char {
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+
+        let char = unwrap!(Expr::Char, root.find_method("main").exprs.get(0).unwrap());
+        let instance = char.instance.as_ref().unwrap();
+        let native = unwrap!(Expr::NewInstance, instance.args.get(0).unwrap());
+        unwrap!(Expr::NativeChar, native.args.get(0).unwrap()).value
+    }
+
+    fn source(literal: &str) -> String {
+        format!(r#"
+class Native__Char
+end
+
+class Char(underlying: Native__Char)
+end
+
+def main: Char
+  {}
+end
+        "#, literal)
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(main_char(&source(r"'\n'")), '\n');
+        assert_eq!(main_char(&source(r"'\''")), '\'');
+        assert_eq!(main_char(&source(r"'\\'")), '\\');
+        assert_eq!(main_char(&source(r"'\x41'")), 'A');
+        assert_eq!(main_char(&source(r"'\u{7E}'")), '~');
+    }
+
+    #[test]
+    #[should_panic(expected = "must contain exactly one char")]
+    fn test_empty() {
+        main_char(&source("''"));
+    }
+
+    #[test]
+    #[should_panic(expected = "must contain exactly one char")]
+    fn test_multiple() {
+        main_char(&source("'ab'"));
+    }
+
+    #[test]
+    #[should_panic(expected = "isn't ASCII")]
+    fn test_non_ascii() {
+        main_char(&source("'é'"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown escape sequence \\q at line 8, column 4")]
+    fn test_unknown_escape() {
+        main_char(&source(r"'\q'"));
+    }
 }
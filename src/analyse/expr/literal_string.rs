@@ -1,24 +1,50 @@
 use parse::tree::{LiteralString, NewInstance, Expr, NativeString};
 use analyse::scope::Scope;
 use std::cell::Cell;
+use tokenize::escape;
+use tokenize::span::Span;
+use tokenize::combinator::take;
+use analyse::error::{Error, AnalyseResult};
 
 pub fn apply<'def>(
     string: &mut LiteralString<'def>,
     scope: &mut Scope<'def>,
-) {
-    string.instance = Some(Box::new(NewInstance {
+) -> AnalyseResult<'def> {
+    let fragment = string.span.fragment;
+    let raw = Span { col: string.span.col + 1, fragment: &fragment[1..(fragment.len() - 1)], ..string.span };
+    string.instance = Some(Box::new(build_instance(unescape(raw)?, string.span, scope)?));
+
+    Ok(())
+}
+
+// Unescapes the content of a string literal. The span excludes the quotes.
+pub fn unescape<'def>(raw: Span<'def>) -> Result<String, Error<'def>> {
+    escape::unescape(raw.fragment).map_err(|(offset, reason)| {
+        let (_, location) = take(raw.fragment[..offset].chars().count(), raw);
+        Error::new(location, reason.to_string())
+    })
+}
+
+pub fn build_instance<'def>(
+    value: String,
+    span: Span<'def>,
+    scope: &mut Scope<'def>,
+) -> Result<NewInstance<'def>, Error<'def>> {
+    Ok(NewInstance {
         name_opt: None,
         args: vec![
             Expr::NewInstance(Box::new(NewInstance {
                 name_opt: None,
                 args: vec![
-                    Expr::NativeString(Box::new(NativeString { value: serde_json::from_str(string.span.fragment).unwrap() }))
+                    Expr::NativeString(Box::new(NativeString { value }))
                 ],
-                class_def: Some(scope.find_class("Native__String").unwrap().parse)
+                class_def: Some(scope.require_class("Native__String", span)?),
+                constructor_def: None
             })),
         ],
-        class_def: Some(scope.find_class("String").unwrap().parse)
-    }));
+        class_def: Some(scope.require_class("String", span)?),
+        constructor_def: None
+    })
 }
 
 #[cfg(test)]
@@ -66,13 +92,32 @@ end
                                 args: vec![
                                     Expr::NativeString(Box::new(NativeString { value: "test".to_string() }))
                                 ],
-                                class_def: Some(root.find_class("Native__String"))
+                                class_def: Some(root.find_class("Native__String")),
+                                constructor_def: None
                             })),
                         ],
-                        class_def: Some(root.find_class("String"))
+                        class_def: Some(root.find_class("String")),
+                        constructor_def: None
                     }
                 ))
             }))
         )
     }
+
+    #[test]
+    #[should_panic(expected = "Unknown escape sequence \\q at line 5, column 6")]
+    fn test_invalid_escape() {
+        let content = r#"
+class Void
+end
+
+def main: Void
+  "ab\q"
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+    }
 }
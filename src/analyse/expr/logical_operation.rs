@@ -0,0 +1,87 @@
+use parse::tree::{LogicalOperation, Expr};
+use analyse::scope::Scope;
+use analyse::expr;
+use analyse::tpe;
+use analyse::error::{Error, AnalyseResult};
+
+pub fn apply<'def>(
+    operation: &mut LogicalOperation<'def>,
+    scope: &mut Scope<'def>,
+) -> AnalyseResult<'def> {
+    let operator = operation.operator;
+    let boolean_class = scope.require_class("Boolean", operator)?;
+
+    for operand in vec![&mut operation.left, &mut operation.right] {
+        expr::apply_value(operand, scope)?;
+
+        let class = tpe::require_type(operand, operator, scope)?;
+        if class as *const _ != boolean_class {
+            return Err(Error::new(
+                operator,
+                format!("The operands of {} must be Boolean, found {}", operator.fragment, class.name.fragment)
+            ));
+        }
+    }
+
+    operation.class_def = Some(boolean_class);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use index;
+    use parse;
+    use analyse::apply;
+    use parse::tree::Expr;
+    use std::ops::{Deref, DerefMut};
+
+    const CLASSES: &str = r#"
+class Native__Bool
+end
+
+class Boolean(underlying: Native__Bool)
+end
+
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+  def ==(other: Int): Boolean
+    true
+  end
+end
+"#;
+
+    #[test]
+    fn test_simple() {
+        let content = format!(r#"{}
+def main: Boolean
+  1 == 2 || false && true
+end
+        "#, CLASSES);
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+
+        let operation = unwrap!(Expr::LogicalOperation, root.find_method("main").exprs.get(0).unwrap());
+        assert_eq!(operation.class_def, Some(root.find_class("Boolean") as *const _));
+
+        let equal = unwrap!(Expr::Invoke, &operation.left);
+        assert_eq!(equal.method_def, Some(root.find_class("Int").find_method("==") as *const _));
+    }
+
+    #[test]
+    #[should_panic(expected = "The operands of && must be Boolean, found Int at line 17, column 8")]
+    fn test_non_boolean() {
+        let content = format!(r#"{}
+def main: Boolean
+  true && 1
+end
+        "#, CLASSES);
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+    }
+}
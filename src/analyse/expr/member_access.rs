@@ -1,21 +1,29 @@
 use analyse::scope::Scope;
 use parse::tree::MemberAccess;
 use analyse::expr;
-use analyse::tpe::GetType;
+use analyse::tpe;
+use analyse::error::{Error, AnalyseResult};
 
 pub fn apply<'def>(
     member_access: &mut MemberAccess<'def>,
     scope: &mut Scope<'def>,
-) {
-    expr::apply(&mut member_access.parent, scope);
+) -> AnalyseResult<'def> {
+    expr::apply_value(&mut member_access.parent, scope)?;
 
-    let class = member_access.parent.get_type(scope);
+    let name = member_access.name.unwrap();
+    let class = tpe::require_type(&member_access.parent, name, scope)?;
 
-    for param in &class.params {
-        if param.name.unwrap().fragment == member_access.name.unwrap().fragment {
+    for param in class.get_members() {
+        if param.name.unwrap().fragment == name.fragment {
             member_access.param_def = Some(param);
         }
     }
+
+    if member_access.param_def.is_none() {
+        return Err(Error::new(name, format!("Unable to find the member {} in the class {}", name.fragment, class.name.fragment)));
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -65,14 +73,17 @@ end
                                             args: vec![
                                                 Expr::NativeString(Box::new(NativeString { value: "a".to_string() }))
                                             ],
-                                            class_def: Some(root.find_class("Native__String"))
+                                            class_def: Some(root.find_class("Native__String")),
+                                            constructor_def: None
                                         })),
                                     ],
-                                    class_def: Some(root.find_class("String"))
+                                    class_def: Some(root.find_class("String")),
+                                    constructor_def: None
                                 }
                             ))
                         }))],
-                        class_def: Some(root.find_class("Test"))
+                        class_def: Some(root.find_class("Test")),
+                        constructor_def: None
                     })),
                     name: Some(span2(11, 13, "member", file.deref())),
                     param_def: Some(root.find_class("Test").params.get(0).unwrap())
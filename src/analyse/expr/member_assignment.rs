@@ -0,0 +1,217 @@
+use analyse::scope::Scope;
+use parse::tree::{MemberAssignment, ParamParent, Expr, Method};
+use analyse::expr;
+use analyse::expr::member_access;
+use analyse::error::{Error, AnalyseResult};
+
+pub fn apply<'def>(
+    member_assignment: &mut MemberAssignment<'def>,
+    scope: &mut Scope<'def>,
+) -> AnalyseResult<'def> {
+    member_access::apply(&mut member_assignment.member, scope)?;
+    expr::apply_value(&mut member_assignment.expr, scope)?;
+
+    let field = unsafe { &*member_assignment.member.param_def.unwrap() };
+
+    if field.is_mutable {
+        return Ok(());
+    }
+
+    // An immutable field without a value can only be populated on `self` by the initializer of its class.
+    let is_initializer = match (scope.find_parent_method_opt(), field.parent, &member_assignment.member.parent) {
+        (Some(parent_method), Some(ParamParent::Class(class)), Expr::SelfInstance(_)) => {
+            let class = unsafe { &*class };
+            class.find_initializer().map(|m| m as *const Method<'def>) == Some(parent_method as *const Method<'def>)
+                && field.value_opt.is_none()
+                && field.index >= class.params.len()
+        },
+        _ => false,
+    };
+
+    if !is_initializer {
+        let name = member_assignment.member.name.unwrap();
+        return Err(Error::new(name, format!("Unable to assign the immutable member {}", name.fragment)));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use index;
+    use parse;
+    use analyse::apply;
+    use parse::tree::Expr;
+    use std::ops::{Deref, DerefMut};
+
+    #[test]
+    fn test_var() {
+        let content = r#"
+class Void
+end
+
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+class Counter
+  var count: Int = 0
+end
+
+def main(): Int
+  counter = Counter()
+  counter.count = 1
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+
+        let counter_class = root.find_class("Counter");
+        let member_assignment = unwrap!(Expr::MemberAssignment, root.find_method("main").exprs.get(1).unwrap());
+        assert_eq!(member_assignment.member.param_def, Some(counter_class.find_field("count") as *const _));
+        assert_eq!(counter_class.find_field("count").index, 0);
+    }
+
+    #[test]
+    fn test_val_in_initializer() {
+        let content = r#"
+class Void
+end
+
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+class Counter
+  val count: Int
+
+  def init(): Void
+    self.count = 1
+  end
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+
+        let counter_class = root.find_class("Counter");
+        let member_assignment = unwrap!(Expr::MemberAssignment, counter_class.find_method("init").exprs.get(0).unwrap());
+        assert_eq!(member_assignment.member.param_def, Some(counter_class.find_field("count") as *const _));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unable to assign the immutable member count")]
+    fn test_val() {
+        let content = r#"
+class Void
+end
+
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+class Counter
+  val count: Int = 0
+end
+
+def main(): Int
+  counter = Counter()
+  counter.count = 1
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unable to assign the immutable member a")]
+    fn test_class_param() {
+        let content = r#"
+class Void
+end
+
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+class Test(a: Int)
+end
+
+def main(): Int
+  test = Test(1)
+  test.a = 2
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+    }
+
+    #[test]
+    #[should_panic(expected = "The immutable field count of the class Counter must be assigned by `self.count = ...` in `def init(): Void` at line 11, column 7")]
+    fn test_unassigned_val() {
+        let content = r#"
+class Void
+end
+
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+class Counter
+  val count: Int
+
+  def init(): Void
+  end
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unable to assign the immutable member count")]
+    fn test_val_of_another_instance_in_initializer() {
+        let content = r#"
+class Void
+end
+
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+class Counter(other: Counter)
+  val count: Int
+
+  def init(): Void
+    self.count = 1
+    other.count = 2
+  end
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+    }
+}
@@ -1,28 +1,57 @@
 use parse::tree::Expr;
 use analyse::scope::Scope;
+use analyse::error::{Error, AnalyseResult};
 
 pub mod assignment;
+pub mod boolean;
+pub mod float;
 pub mod identifier;
 pub mod int;
+pub mod interpolation;
 pub mod invoke;
+pub mod logical_operation;
 pub mod literal_char;
 pub mod literal_string;
 pub mod member_access;
+pub mod member_assignment;
 pub mod new_instance;
+pub mod self_instance;
+pub mod using;
 
 pub fn apply<'def>(
     expr: &mut Expr<'def>,
     scope: &mut Scope<'def>,
-) {
+) -> AnalyseResult<'def> {
     match expr {
         Expr::Invoke(e) => invoke::apply(e, scope),
         Expr::Int(e) => int::apply(e, scope),
+        Expr::Float(e) => float::apply(e, scope),
+        Expr::Interpolation(e) => interpolation::apply(e, scope),
         Expr::String(e) => literal_string::apply(e, scope),
         Expr::Char(e) => literal_char::apply(e, scope),
+        Expr::Boolean(e) => boolean::apply(e, scope),
+        Expr::LogicalOperation(e) => logical_operation::apply(e, scope),
         Expr::Identifier(e) => identifier::apply(e, scope),
         Expr::MemberAccess(e) => member_access::apply(e, scope),
+        Expr::MemberAssignment(e) => member_assignment::apply(e, scope),
         Expr::NewInstance(e) => new_instance::apply(e, scope),
+        Expr::SelfInstance(e) => self_instance::apply(e, scope),
         Expr::Assignment(e) => assignment::apply(e, scope),
+        Expr::Using(e) => using::apply(e, scope),
         other => panic!("Unsupported expr {:#?}", other),
     }
 }
+
+// An expr whose value is used, e.g. assigned or passed as an arg. A panic never returns, so it has no value and is only
+// allowed as a statement, e.g. the last expr of a method.
+pub fn apply_value<'def>(
+    expr: &mut Expr<'def>,
+    scope: &mut Scope<'def>,
+) -> AnalyseResult<'def> {
+    apply(expr, scope)?;
+
+    match expr {
+        Expr::Invoke(e) if e.is_panic() => Err(Error::new(e.name, "Unable to use panic as a value because it never returns".to_string())),
+        _ => Ok(()),
+    }
+}
@@ -1,17 +1,137 @@
-use parse::tree::NewInstance;
+use parse::tree::{NewInstance, Class, Method, Param, Expr};
 use analyse::scope::Scope;
 use analyse::expr;
+use analyse::tpe::GetType;
+use analyse::error::{Error, AnalyseResult};
+use tokenize::span::Span;
 
 pub fn apply<'def>(
     new_instance: &mut NewInstance<'def>,
     scope: &mut Scope<'def>,
-) {
+) -> AnalyseResult<'def> {
     match new_instance.name_opt {
-       Some(name) => new_instance.class_def = scope.find_class(name.fragment).map(|c|c.parse),
+       Some(name) => new_instance.class_def = Some(scope.require_class(name.fragment, name)?),
        None => (),
     };
 
     for arg in &mut new_instance.args {
-       expr::apply(arg, scope);
+       expr::apply_value(arg, scope)?;
+    }
+
+    if let (Some(class), Some(name)) = (new_instance.class_def, new_instance.name_opt) {
+        new_instance.constructor_def = find_constructor(unsafe { &*class }, &new_instance.args, name, scope)?;
+    }
+
+    Ok(())
+}
+
+fn find_constructor<'def>(
+    class: &Class<'def>,
+    args: &[Expr<'def>],
+    name: Span<'def>,
+    scope: &Scope<'def>,
+) -> Result<Option<*const Method<'def>>, Error<'def>> {
+    let constructors = class.find_constructors();
+
+    if constructors.is_empty() {
+        return Ok(None);
+    }
+
+    let mut candidates = vec![];
+
+    if class.params.len() == args.len() {
+        candidates.push(None);
+    }
+
+    for constructor in constructors {
+        if constructor.params.len() == args.len() {
+            candidates.push(Some(constructor as *const Method<'def>));
+        }
+    }
+
+    if candidates.len() > 1 {
+        candidates.retain(|candidate| {
+            let params = match candidate {
+                Some(constructor) => &unsafe { &**constructor }.params,
+                None => &class.params,
+            };
+            match_types(params, args, scope)
+        });
+    }
+
+    match candidates.len() {
+        1 => Ok(candidates[0]),
+        0 => Err(Error::new(name, format!("Unable to find a constructor of the class {} that takes {} args", class.name.fragment, args.len()))),
+        _ => Err(Error::new(name, format!("Ambiguous constructors of the class {} for {} args", class.name.fragment, args.len()))),
+    }
+}
+
+fn match_types<'def>(
+    params: &[Param<'def>],
+    args: &[Expr<'def>],
+    scope: &Scope<'def>,
+) -> bool {
+    params.iter().zip(args.iter()).all(|(param, arg)| {
+        param.tpe.span.map(|s| s.fragment) == arg.find_type(scope).map(|c| c.name.fragment)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use index;
+    use parse;
+    use analyse::apply;
+    use parse::tree::{Expr, NewInstance};
+    use test_common::span2;
+    use std::ops::{Deref, DerefMut};
+
+    #[test]
+    fn test_constructor() {
+        let content = r#"
+class Void
+end
+
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+class Test(a: Int)
+  def init(): Void
+  end
+
+  def init(): Test
+    Test(1)
+  end
+end
+
+def main(): Test
+  Test()
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+
+        let test_class = root.find_class("Test");
+        assert_eq!(test_class.find_initializer(), test_class.methods.get(0));
+        assert_eq!(test_class.find_constructors(), vec![test_class.methods.get(1).unwrap()]);
+        assert_eq!(
+            root.find_method("main").exprs,
+            vec![
+                Expr::NewInstance(Box::new(NewInstance {
+                    name_opt: Some(span2(20, 3, "Test", file.deref())),
+                    args: vec![],
+                    class_def: Some(test_class),
+                    constructor_def: Some(test_class.methods.get(1).unwrap()),
+                }))
+            ]
+        );
+
+        let primary = unwrap!(Expr::NewInstance, test_class.methods.get(1).unwrap().exprs.get(0).unwrap());
+        assert_eq!(primary.class_def, Some(test_class as *const _));
+        assert_eq!(primary.constructor_def, None);
     }
 }
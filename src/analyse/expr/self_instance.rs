@@ -0,0 +1,105 @@
+use parse::tree::{SelfInstance, Param};
+use analyse::scope::Scope;
+use analyse::error::{Error, AnalyseResult};
+
+pub fn apply<'def>(
+    self_instance: &mut SelfInstance<'def>,
+    scope: &mut Scope<'def>,
+) -> AnalyseResult<'def> {
+    match find_receiver(scope) {
+        Some(receiver) => self_instance.param_def = Some(receiver),
+        None => return Err(Error::new(self_instance.span.unwrap(), "`self` can only be used inside an instance method".to_string())),
+    }
+
+    Ok(())
+}
+
+pub fn find_receiver<'def>(scope: &Scope<'def>) -> Option<*const Param<'def>> {
+    let method = scope.find_parent_method_opt()?;
+
+    match method.parent_class {
+        Some(class) if !unsafe { &*class }.is_constructor(method) && !method.is_static => Some(method.params.get(0).unwrap()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use index;
+    use parse;
+    use analyse::apply;
+    use parse::tree::{Expr, SelfInstance, Invoke};
+    use test_common::span2;
+    use std::ops::{Deref, DerefMut};
+
+    #[test]
+    fn test_simple() {
+        let content = r#"
+class Void
+end
+
+class Test
+  def run(): Test
+    self
+  end
+
+  def go(): Test
+    run()
+  end
+end
+
+def run(): Void
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+
+        let test_class = root.find_class("Test");
+        let run_method = test_class.find_method("run");
+        assert_eq!(
+            run_method.exprs,
+            vec![
+                Expr::SelfInstance(Box::new(SelfInstance {
+                    span: Some(span2(6, 5, "self", file.deref())),
+                    param_def: Some(run_method.params.get(0).unwrap()),
+                }))
+            ]
+        );
+
+        let go_method = test_class.find_method("go");
+        assert_eq!(
+            go_method.exprs,
+            vec![
+                Expr::Invoke(Box::new(Invoke {
+                    invoker_opt: Some(Expr::SelfInstance(Box::new(SelfInstance {
+                        span: None,
+                        param_def: Some(go_method.params.get(0).unwrap()),
+                    }))),
+                    name: span2(10, 5, "run", file.deref()),
+                    args: vec![],
+                    method_def: Some(run_method),
+                    static_invoker_opt: None,
+                }))
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "`self` can only be used inside an instance method")]
+    fn test_outside_class() {
+        let content = r#"
+class Void
+end
+
+def run(): Void
+  self
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+    }
+}
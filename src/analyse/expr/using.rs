@@ -0,0 +1,122 @@
+use parse::tree::{Using, Class};
+use analyse::scope::Scope;
+use analyse::expr;
+use analyse::expr::assignment;
+use analyse::tpe;
+use analyse::error::{Error, AnalyseResult};
+
+pub fn apply<'def>(
+    using: &mut Using<'def>,
+    scope: &mut Scope<'def>,
+) -> AnalyseResult<'def> {
+    // The variable is only visible inside the block. On error, the method drops the levels that are left open.
+    scope.enter();
+    assignment::apply(&mut using.assignment, scope)?;
+
+    let class = unsafe { &*using.assignment.tpe.unwrap() };
+    if class.find_finalizer().is_none() {
+        return Err(Error::new(
+            using.span,
+            format!(
+                "Unable to use {} because the class {} doesn't declare `def finalize(): Void`",
+                using.assignment.name.fragment,
+                class.name.fragment
+            )
+        ));
+    }
+
+    for e in &mut using.exprs {
+        expr::apply(e, scope)?;
+    }
+
+    using.class_def = Some(match using.exprs.last() {
+        Some(e) => tpe::require_type(e, using.span, scope)? as *const Class,
+        None => scope.require_class("Void", using.span)?,
+    });
+    scope.leave();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::{Deref, DerefMut};
+
+    use index;
+    use parse;
+    use analyse::apply;
+    use parse::tree::{Class, Expr, IdentifierSource};
+
+    const CLASSES: &str = r#"
+class Void
+end
+
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+class Pipe(fd: Int)
+  def finalize(): Void
+  end
+
+  def read(): Int
+    fd
+  end
+end
+    "#;
+
+    #[test]
+    fn test_using() {
+        let content = format!("{}{}", CLASSES, r#"
+def main: Int
+  using pipe = Pipe(3)
+    pipe.read()
+  end
+end
+        "#);
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+
+        let using = unwrap!(Expr::Using, root.find_method("main").exprs.get(0).unwrap());
+        assert_eq!(using.assignment.tpe, Some(root.find_class("Pipe") as *const Class));
+        assert_eq!(using.class_def, Some(root.find_class("Int") as *const Class));
+
+        let invoke = unwrap!(Expr::Invoke, using.exprs.get(0).unwrap());
+        let identifier = unwrap!(Expr::Identifier, invoke.invoker_opt.as_ref().unwrap());
+        assert_eq!(identifier.source, Some(IdentifierSource::Assignment(&using.assignment)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unable to use a because the class Int doesn't declare `def finalize(): Void` at line 20, column 3")]
+    fn test_without_finalizer() {
+        let content = format!("{}{}", CLASSES, r#"
+def main: Void
+  using a = 1
+  end
+end
+        "#);
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+    }
+
+    #[test]
+    #[should_panic(expected = "The finalizer of the class Socket must be declared as `def finalize(): Void`")]
+    fn test_invalid_finalizer() {
+        let content = format!("{}{}", CLASSES, r#"
+class Socket
+  def finalize(): Int
+    1
+  end
+end
+        "#);
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        apply(&mut [file.deref_mut()], &root);
+    }
+}
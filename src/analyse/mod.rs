@@ -2,25 +2,46 @@ use LilitFile;
 use analyse::scope::Scope;
 use parse::tree::{CompilationUnitItem, Class, Method};
 use analyse::def::{class, method};
+use analyse::error::Error;
 use index::tree::Root;
 
 pub mod def;
+pub mod error;
 pub mod expr;
 pub mod scope;
 pub mod tpe;
 
+// The compiler stops at the errors, so they're reported all at once.
 pub fn apply<'def>(
     files: &mut [&mut LilitFile<'def>],
     root: &Root<'def>,
 ) {
+    let errors = check(files, root);
+
+    if !errors.is_empty() {
+        panic!("{}", errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n"));
+    }
+}
+
+// An error only stops the analysis of the class member or the method that contains it, so the rest of the files is
+// still analysed, e.g. for the language server.
+pub fn check<'def>(
+    files: &mut [&mut LilitFile<'def>],
+    root: &Root<'def>,
+) -> Vec<Error<'def>> {
+    let mut errors = vec![];
+
     for file in files {
-        apply_file(file, root);
+        apply_file(file, root, &mut errors);
     }
+
+    errors
 }
 
 pub fn apply_file<'def>(
     file: &mut LilitFile<'def>,
     root: &Root<'def>,
+    errors: &mut Vec<Error<'def>>,
 ) {
     let mut scope = Scope { levels: vec![] };
     scope.enter_root(root);
@@ -28,8 +49,12 @@ pub fn apply_file<'def>(
 
     for item in &mut file.unit.items {
         match item {
-            CompilationUnitItem::Class(c) => class::apply(c, &mut scope),
-            CompilationUnitItem::Method(m) => method::apply(m, None, &mut scope),
+            CompilationUnitItem::Class(c) => class::apply(c, &mut scope, errors),
+            CompilationUnitItem::Method(m) => {
+                if let Err(error) = method::apply(m, None, &mut scope) {
+                    errors.push(error);
+                }
+            },
         }
     }
     scope.leave();
@@ -99,7 +124,9 @@ end
                     CompilationUnitItem::Class(Class {
                         name: span2(1, 7, "Number", file.deref()),
                         params: vec![],
+                        fields: vec![],
                         methods: vec![],
+                        doc_comments: vec![],
                         llvm: Cell::new(None),
                         llvm_native: Cell::new(None)
                     }),
@@ -108,7 +135,9 @@ end
                         params: vec![],
                         exprs: vec![],
                         return_type: Type { span: Some(span2(4, 13, "Number", file.deref())), class_def: Some(root.find_class("Number")) },
+                        is_static: false,
                         parent_class: None,
+                        doc_comments: vec![],
                         llvm: Cell::new(None)
                     }),
                     CompilationUnitItem::Method(Method {
@@ -120,10 +149,13 @@ end
                                 name: span2(8, 3, "test", file.deref()),
                                 args: vec![],
                                 method_def: Some(root.find_method("test")),
+                                static_invoker_opt: None,
                             }))
                         ],
                         return_type: Type { span: Some(span2(7, 13, "Number", file.deref())), class_def: Some(root.find_class("Number")) },
+                        is_static: false,
                         parent_class: None,
+                        doc_comments: vec![],
                         llvm: Cell::new(None)
                     }),
                 ]
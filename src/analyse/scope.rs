@@ -1,6 +1,8 @@
 use parse::tree::{CompilationUnit, Class, Method, Assignment, Param, IdentifierSource};
 use index::tree::{Root, RootItem};
 use ::{index, parse};
+use analyse::error::Error;
+use tokenize::span::Span;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Scope<'def> {
@@ -99,7 +101,7 @@ impl <'def> Scope<'def> {
             }
         }
 
-        panic!();
+        None
     }
 
     pub fn find_class(&self, name: &str) -> Option<&index::tree::Class<'def>> {
@@ -120,7 +122,15 @@ impl <'def> Scope<'def> {
             }
         }
 
-        panic!("Unable to find the class {}", name);
+        None
+    }
+
+    // The literals are instances of the classes declared in Lilit, e.g. `1` is an Int, so they must exist.
+    pub fn require_class(&self, name: &str, span: Span<'def>) -> Result<*const Class<'def>, Error<'def>> {
+        match self.find_class(name) {
+            Some(class) => Ok(class.parse),
+            None => Err(Error::new(span, format!("Unable to find the class {}", name))),
+        }
     }
 
     pub fn find_identifier(&self, name: &str) -> Option<IdentifierSource<'def>> {
@@ -137,7 +147,7 @@ impl <'def> Scope<'def> {
             match level.enclosing_opt {
                 Some(LevelEnclosing::Class(class)) => {
                     let class = unsafe { &*class };
-                    for param in &unsafe { &*class.parse }.params {
+                    for param in unsafe { &*class.parse }.get_members() {
                         if param.name.unwrap().fragment == name {
                             return Some(IdentifierSource::Param(param));
                         }
@@ -155,23 +165,30 @@ impl <'def> Scope<'def> {
             }
         }
 
-        panic!("Unable to find the class {}", name);
+        None
     }
 
     pub fn find_parent_method(&self) -> &Method<'def> {
+        match self.find_parent_method_opt() {
+            Some(method) => method,
+            None => panic!("Unable to find the parent method"),
+        }
+    }
+
+    // There's no parent method in the default value of a field.
+    pub fn find_parent_method_opt(&self) -> Option<&Method<'def>> {
         for i in (0..self.levels.len()).rev() {
             let level = self.levels.get(i).unwrap();
 
             match level.enclosing_opt {
                 Some(LevelEnclosing::Method(method)) => {
-                    return unsafe { &*(&*method).parse };
+                    return Some(unsafe { &*(&*method).parse });
                 },
                 _ => (),
             }
         }
 
-        panic!("Unable to find the parent method");
-
+        None
     }
 
     pub fn add_var(&mut self, assignment: &Assignment<'def>) {
@@ -1,35 +1,74 @@
-use parse::tree::{Type, Class, Expr};
+use parse::tree::{Type, Class, Expr, IdentifierSource};
 use analyse::scope::Scope;
-use std::borrow::Borrow;
+use analyse::error::{Error, AnalyseResult};
+use tokenize::span::Span;
 
 pub fn apply<'def>(
     tpe: &mut Type<'def>,
     scope: &mut Scope<'def>
-) {
+) -> AnalyseResult<'def> {
     if tpe.class_def.is_none() {
-        tpe.class_def = scope.find_class(tpe.span.unwrap().fragment).map(|c| c.parse);
+        let span = tpe.span.unwrap();
+        match scope.find_class(span.fragment) {
+            Some(class) => tpe.class_def = Some(class.parse),
+            None => return Err(Error::new(span, format!("Unable to find the class {}", span.fragment))),
+        }
     }
+
+    Ok(())
 }
 
+// The type is unknown when the declaration that it comes from has an error, e.g. a param whose class doesn't exist.
+pub fn require_type<'e, 'def>(
+    expr: &'e Expr<'def>,
+    span: Span<'def>,
+    scope: &Scope<'def>,
+) -> Result<&'e Class<'def>, Error<'def>> {
+    match expr.find_type(scope) {
+        Some(class) => Ok(class),
+        None => Err(Error::new(span, format!("Unable to infer the type of {}", span.fragment))),
+    }
+}
 
 pub trait GetType<'def> {
-    fn get_type(&self, scope: &Scope<'def>) -> &Class<'def>;
+    // Only an analysed expr without errors has a type.
+    fn get_type(&self, scope: &Scope<'def>) -> &Class<'def> {
+        self.find_type(scope).expect("The expr hasn't been analysed")
+    }
+
+    fn find_type(&self, scope: &Scope<'def>) -> Option<&Class<'def>>;
 }
 
 impl <'def> GetType<'def> for Expr<'def> {
-    fn get_type(&self, scope: &Scope<'def>) -> &Class<'def> {
-        match self {
-            Expr::Identifier(i) => unsafe { &*i.source.borrow().as_ref().unwrap().get_type() },
-            Expr::MemberAccess(i) => unsafe { &*(&*i.param_def.unwrap()).tpe.class_def.unwrap() },
-            Expr::NewInstance(i) => unsafe { &*i.class_def.unwrap() },
-            Expr::Int(i) => unsafe { &*scope.find_class("Int").unwrap().parse },
-            Expr::String(i) => unsafe { &*scope.find_class("String").unwrap().parse },
-            Expr::Char(i) => unsafe { &*scope.find_class("Char").unwrap().parse },
-            Expr::NativeInt(i) => unsafe { &*scope.find_class("Native__Int").unwrap().parse },
-            Expr::NativeString(i) => unsafe { &*scope.find_class("Native__String").unwrap().parse },
-            Expr::NativeChar(i) => unsafe { &*scope.find_class("Native__Char").unwrap().parse },
-            Expr::Invoke(i) => unsafe { &*(&*i.method_def.unwrap()).return_type.class_def.unwrap() },
-            Expr::Assignment(i) => unsafe { &*i.tpe.unwrap() },
-        }
+    fn find_type(&self, scope: &Scope<'def>) -> Option<&Class<'def>> {
+        let class = match self {
+            Expr::Identifier(i) => match i.source.as_ref()? {
+                IdentifierSource::Assignment(a) => unsafe { &**a }.tpe?,
+                IdentifierSource::Param(p) => unsafe { &**p }.tpe.class_def?,
+                IdentifierSource::ClassParam(p) => unsafe { &*p.param_def? }.tpe.class_def?,
+            },
+            Expr::MemberAccess(i) => unsafe { &*i.param_def? }.tpe.class_def?,
+            Expr::NewInstance(i) => i.class_def?,
+            Expr::SelfInstance(i) => unsafe { &*i.param_def? }.tpe.class_def?,
+            Expr::Int(_) => scope.find_class("Int")?.parse,
+            Expr::Float(_) => scope.find_class("Float")?.parse,
+            Expr::String(_) => scope.find_class("String")?.parse,
+            Expr::Interpolation(i) => i.class_def?,
+            Expr::Boolean(_) => scope.find_class("Boolean")?.parse,
+            Expr::LogicalOperation(i) => i.class_def?,
+            Expr::Char(_) => scope.find_class("Char")?.parse,
+            Expr::NativeInt(_) => scope.find_class("Native__Int")?.parse,
+            Expr::NativeFloat(_) => scope.find_class("Native__Float")?.parse,
+            Expr::NativeString(_) => scope.find_class("Native__String")?.parse,
+            Expr::NativeBool(_) => scope.find_class("Native__Bool")?.parse,
+            Expr::NativeChar(_) => scope.find_class("Native__Char")?.parse,
+            Expr::Invoke(i) if i.is_panic() => scope.find_class("Void")?.parse,
+            Expr::Invoke(i) => unsafe { &*i.method_def? }.return_type.class_def?,
+            Expr::Assignment(i) => i.tpe?,
+            Expr::MemberAssignment(i) => return i.expr.find_type(scope),
+            Expr::Using(i) => i.class_def?,
+        };
+
+        Some(unsafe { &*class })
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,212 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use index::tree::{Root, RootItem};
+use parse::tree::{Class, Method, Param, Type};
+
+// Writes `index.html`, which lists all classes and top-level methods, and one page per class.
+// The tree must be analysed because the links between types come from `Type.class_def`.
+pub fn apply(root: &Root, output_dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(output_dir)?;
+    fs::write(output_dir.join("index.html"), render_index(root))?;
+
+    for item in &root.items {
+        if let RootItem::Class(class) = item {
+            let class = unsafe { &*class.parse };
+            fs::write(output_dir.join(get_page_name(class)), render_class(class))?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn render_index(root: &Root) -> String {
+    let mut classes = String::new();
+    let mut methods = String::new();
+
+    for item in &root.items {
+        match item {
+            RootItem::Class(class) => {
+                let class = unsafe { &*class.parse };
+                classes.push_str(&format!(
+                    "<li><a href=\"{}\">{}</a>{}</li>\n",
+                    get_page_name(class),
+                    escape(class.name.fragment),
+                    render_summary(&class.get_doc())
+                ));
+            },
+            RootItem::Method(method) => methods.push_str(&render_method(unsafe { &*method.parse })),
+        }
+    }
+
+    render_page(
+        "Index",
+        &format!("<h1>Index</h1>\n<h2>Classes</h2>\n<ul>\n{}</ul>\n<h2>Methods</h2>\n{}", classes, methods)
+    )
+}
+
+pub fn render_class(class: &Class) -> String {
+    let mut body = format!("<p><a href=\"index.html\">Index</a></p>\n<h1>{}</h1>\n", escape(class.name.fragment));
+    body.push_str(&format!("<pre>class {}{}</pre>\n", escape(class.name.fragment), render_params(&class.params)));
+    body.push_str(&render_doc(&class.get_doc()));
+
+    if !class.fields.is_empty() {
+        body.push_str("<h2>Fields</h2>\n<ul>\n");
+        for field in &class.fields {
+            body.push_str(&format!(
+                "<li><code>{} {}</code></li>\n",
+                if field.is_mutable { "var" } else { "val" },
+                render_param(field)
+            ));
+        }
+        body.push_str("</ul>\n");
+    }
+
+    if !class.methods.is_empty() {
+        body.push_str("<h2>Methods</h2>\n");
+        for method in &class.methods {
+            body.push_str(&render_method(method));
+        }
+    }
+
+    render_page(class.name.fragment, &body)
+}
+
+fn render_method(method: &Method) -> String {
+    format!(
+        "<div class=\"method\" id=\"{}\">\n<pre>{}def {}{}: {}</pre>\n{}</div>\n",
+        escape(method.name.fragment),
+        if method.is_static { "static " } else { "" },
+        escape(method.name.fragment),
+        render_params(&method.params),
+        render_type(&method.return_type),
+        render_doc(&method.get_doc())
+    )
+}
+
+fn render_params(params: &[Param]) -> String {
+    // The implicit receiver of an instance method has no name.
+    let params = params.iter().filter(|p| p.name.is_some()).map(render_param).collect::<Vec<_>>();
+
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("({})", params.join(", "))
+    }
+}
+
+fn render_param(param: &Param) -> String {
+    format!(
+        "{}{}: {}",
+        escape(param.name.unwrap().fragment),
+        if param.is_varargs { "..." } else { "" },
+        render_type(&param.tpe)
+    )
+}
+
+fn render_type(tpe: &Type) -> String {
+    let name = escape(tpe.span.map(|s| s.fragment).unwrap_or(""));
+
+    match tpe.class_def {
+        Some(class) => format!("<a href=\"{}\">{}</a>", get_page_name(unsafe { &*class }), name),
+        None => name,
+    }
+}
+
+fn render_doc(doc: &str) -> String {
+    if doc.is_empty() {
+        return String::new();
+    }
+
+    doc.split("\n\n")
+        .map(|paragraph| format!("<p>{}</p>\n", escape(paragraph)))
+        .collect()
+}
+
+// The first paragraph of the doc is shown next to the class in the index.
+fn render_summary(doc: &str) -> String {
+    match doc.split("\n\n").next() {
+        Some(summary) if !summary.is_empty() => format!(" &mdash; {}", escape(summary)),
+        _ => String::new(),
+    }
+}
+
+fn render_page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        body
+    )
+}
+
+fn get_page_name(class: &Class) -> String {
+    format!("{}.html", class.name.fragment)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::{Deref, DerefMut};
+
+    use index;
+    use parse;
+    use analyse;
+    use doc::{render_class, render_index};
+
+    #[test]
+    fn test_class() {
+        let content = r#"
+class Void
+end
+
+class Native__Int
+end
+
+/// A whole number.
+class Int(underlying: Native__Int)
+end
+
+/// Counts things.
+///
+/// The count <never> goes down.
+class Counter(start: Int)
+  var count: Int = 0
+
+  /// Adds `n` to the count.
+  def add(n: Int): Int
+    count
+  end
+
+  static def zero(): Counter
+    Counter(0)
+  end
+end
+
+def main: Int
+  0
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = index::build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
+
+        let page = render_class(root.find_class("Counter"));
+        assert!(page.contains("<pre>class Counter(start: <a href=\"Int.html\">Int</a>)</pre>"));
+        assert!(page.contains("<p>The count &lt;never&gt; goes down.</p>"));
+        assert!(page.contains("<code>var count: <a href=\"Int.html\">Int</a></code>"));
+        assert!(page.contains("<pre>def add(n: <a href=\"Int.html\">Int</a>): <a href=\"Int.html\">Int</a></pre>\n<p>Adds `n` to the count.</p>"));
+        assert!(page.contains("<pre>static def zero: <a href=\"Counter.html\">Counter</a></pre>"));
+
+        let index = render_index(&root);
+        assert!(index.contains("<li><a href=\"Counter.html\">Counter</a> &mdash; Counts things.</li>"));
+        assert!(index.contains("<pre>def main: <a href=\"Int.html\">Int</a></pre>"));
+    }
+}
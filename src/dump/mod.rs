@@ -0,0 +1,275 @@
+use serde_json::Value as Json;
+
+use analyse::scope::Scope;
+use analyse::tpe::GetType;
+use parse::tree::{
+    Class, CompilationUnit, CompilationUnitItem, Expr, IdentifierSource, InterpolationPart, MemberAccess, Method,
+    NewInstance, Param, Type,
+};
+use tokenize::span::Span;
+use tokenize::token::Token;
+
+// The JSON forms of the tokens and the trees are for tools, e.g. `lilit --emit=ast`.
+
+pub fn apply_tokens(tokens: &[Token]) -> Json {
+    Json::Array(
+        tokens
+            .iter()
+            .map(|token| {
+                let kind = match token {
+                    Token::Capitalize(_) => "Capitalize",
+                    Token::Char(_) => "Char",
+                    Token::Comment(_) => "Comment",
+                    Token::DocComment(_) => "DocComment",
+                    Token::Float(_) => "Float",
+                    Token::Identifier(_) => "Identifier",
+                    Token::Int(_) => "Int",
+                    Token::Interpolation(_) => "Interpolation",
+                    Token::Keyword(_) => "Keyword",
+                    Token::String(_) => "String",
+                    Token::Symbol(_) => "Symbol",
+                };
+                json!({ "kind": kind, "span": dump_span(token.span()) })
+            })
+            .collect()
+    )
+}
+
+// The references to the definitions are null until the tree is analysed. With a scope, which requires an analysed
+// tree, every expression also has its type.
+pub fn apply(unit: &CompilationUnit, scope: Option<&Scope>) -> Json {
+    let dumper = Dumper { scope };
+
+    Json::Array(
+        unit.items
+            .iter()
+            .map(|item| match item {
+                CompilationUnitItem::Class(class) => dumper.dump_class(class),
+                CompilationUnitItem::Method(method) => dumper.dump_method(method),
+            })
+            .collect()
+    )
+}
+
+struct Dumper<'s, 'def: 's> {
+    scope: Option<&'s Scope<'def>>,
+}
+
+impl <'s, 'def> Dumper<'s, 'def> {
+    fn dump_class(&self, class: &Class<'def>) -> Json {
+        json!({
+            "kind": "Class",
+            "name": dump_span(class.name),
+            "doc": class.get_doc(),
+            "params": self.dump_params(&class.params),
+            "fields": self.dump_params(&class.fields),
+            "methods": class.methods.iter().map(|m| self.dump_method(m)).collect::<Vec<_>>(),
+        })
+    }
+
+    fn dump_method(&self, method: &Method<'def>) -> Json {
+        json!({
+            "kind": "Method",
+            "name": dump_span(method.name),
+            "doc": method.get_doc(),
+            "is_static": method.is_static,
+            "params": self.dump_params(&method.params),
+            "return_type": dump_type(&method.return_type),
+            "exprs": self.dump_exprs(&method.exprs),
+        })
+    }
+
+    fn dump_params(&self, params: &[Param<'def>]) -> Json {
+        Json::Array(
+            params
+                .iter()
+                .map(|p| json!({
+                    "name": p.name.map(dump_span),
+                    "type": dump_type(&p.tpe),
+                    "is_varargs": p.is_varargs,
+                    "is_mutable": p.is_mutable,
+                    "value": p.value_opt.as_ref().map(|v| self.dump_expr(v)),
+                }))
+                .collect()
+        )
+    }
+
+    fn dump_exprs(&self, exprs: &[Expr<'def>]) -> Json {
+        Json::Array(exprs.iter().map(|e| self.dump_expr(e)).collect())
+    }
+
+    fn dump_expr(&self, expr: &Expr<'def>) -> Json {
+        let mut json = match expr {
+            Expr::Assignment(a) => json!({
+                "kind": "Assignment",
+                "name": dump_span(a.name),
+                "expr": self.dump_expr(&a.expr),
+            }),
+            Expr::Boolean(b) => self.dump_literal("Boolean", b.span, &b.instance),
+            Expr::Char(c) => self.dump_literal("Char", c.span, &c.instance),
+            Expr::Float(f) => self.dump_literal("Float", f.span, &f.instance),
+            Expr::Int(i) => self.dump_literal("Int", i.span, &i.instance),
+            Expr::String(s) => self.dump_literal("String", s.span, &s.instance),
+            Expr::Identifier(i) => json!({
+                "kind": "Identifier",
+                "name": i.name.map(dump_span),
+                "source": i.source.as_ref().map(|s| match s {
+                    IdentifierSource::Assignment(a) => json!({ "kind": "Assignment", "name": dump_span(unsafe { &**a }.name) }),
+                    IdentifierSource::Param(p) => json!({ "kind": "Param", "name": unsafe { &**p }.name.map(dump_span) }),
+                    IdentifierSource::ClassParam(m) => json!({ "kind": "ClassParam", "member": self.dump_member_access(m) }),
+                }),
+            }),
+            Expr::Interpolation(i) => json!({
+                "kind": "Interpolation",
+                "span": dump_span(i.span),
+                "parts": i.parts.iter().map(|p| match p {
+                    InterpolationPart::Text(t) => self.dump_literal("String", t.span, &t.instance),
+                    InterpolationPart::Expr(e) => self.dump_expr(e),
+                }).collect::<Vec<_>>(),
+            }),
+            Expr::Invoke(i) => json!({
+                "kind": "Invoke",
+                "name": dump_span(i.name),
+                "invoker": i.invoker_opt.as_ref().map(|e| self.dump_expr(e)),
+                "static_invoker": i.static_invoker_opt.map(dump_span),
+                "args": self.dump_exprs(&i.args),
+                "method": i.method_def.map(|m| get_method_name(unsafe { &*m })),
+            }),
+            Expr::LogicalOperation(l) => json!({
+                "kind": "LogicalOperation",
+                "operator": dump_span(l.operator),
+                "left": self.dump_expr(&l.left),
+                "right": self.dump_expr(&l.right),
+            }),
+            Expr::MemberAccess(m) => self.dump_member_access(m),
+            Expr::MemberAssignment(m) => json!({
+                "kind": "MemberAssignment",
+                "member": self.dump_member_access(&m.member),
+                "expr": self.dump_expr(&m.expr),
+            }),
+            Expr::NativeBool(b) => json!({ "kind": "NativeBool", "value": b.value }),
+            Expr::NativeChar(c) => json!({ "kind": "NativeChar", "value": c.value.to_string() }),
+            Expr::NativeFloat(f) => json!({ "kind": "NativeFloat", "value": f.value }),
+            Expr::NativeInt(i) => json!({ "kind": "NativeInt", "value": i.value }),
+            Expr::NativeString(s) => json!({ "kind": "NativeString", "value": s.value }),
+            Expr::NewInstance(n) => self.dump_new_instance(n),
+            Expr::SelfInstance(s) => json!({ "kind": "SelfInstance", "span": s.span.map(dump_span) }),
+            Expr::Using(u) => json!({
+                "kind": "Using",
+                "name": dump_span(u.assignment.name),
+                "expr": self.dump_expr(&u.assignment.expr),
+                "exprs": self.dump_exprs(&u.exprs),
+            }),
+        };
+
+        if let Some(scope) = self.scope {
+            json["type"] = json!(expr.get_type(scope).name.fragment);
+        }
+
+        json
+    }
+
+    fn dump_literal(&self, kind: &str, span: Span, instance: &Option<Box<NewInstance<'def>>>) -> Json {
+        json!({
+            "kind": kind,
+            "span": dump_span(span),
+            "instance": instance.as_ref().map(|i| self.dump_new_instance(i)),
+        })
+    }
+
+    fn dump_member_access(&self, member: &MemberAccess<'def>) -> Json {
+        json!({
+            "kind": "MemberAccess",
+            "parent": self.dump_expr(&member.parent),
+            "name": member.name.map(dump_span),
+        })
+    }
+
+    fn dump_new_instance(&self, instance: &NewInstance<'def>) -> Json {
+        json!({
+            "kind": "NewInstance",
+            "name": instance.name_opt.map(dump_span),
+            "args": self.dump_exprs(&instance.args),
+            "class": instance.class_def.map(|c| unsafe { &*c }.name.fragment),
+            "constructor": instance.constructor_def.map(|m| get_method_name(unsafe { &*m })),
+        })
+    }
+}
+
+fn dump_span(span: Span) -> Json {
+    json!({ "line": span.line, "col": span.col, "fragment": span.fragment })
+}
+
+fn dump_type(tpe: &Type) -> Json {
+    json!({
+        "span": tpe.span.map(dump_span),
+        "class": tpe.class_def.map(|c| unsafe { &*c }.name.fragment),
+    })
+}
+
+// An instance or static method is named `Class.method`, and a top-level method is just `method`.
+fn get_method_name(method: &Method) -> String {
+    match method.parent_class {
+        Some(class) => format!("{}.{}", unsafe { &*class }.name.fragment, method.name.fragment),
+        None => method.name.fragment.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::{Deref, DerefMut};
+
+    use analyse;
+    use analyse::scope::Scope;
+    use dump::{apply, apply_tokens};
+    use index;
+    use parse;
+    use test_common::generate_tokens;
+
+    #[test]
+    fn test_tokens() {
+        assert_eq!(
+            apply_tokens(&generate_tokens("a(1)")),
+            json!([
+                { "kind": "Identifier", "span": { "line": 1, "col": 1, "fragment": "a" } },
+                { "kind": "Symbol", "span": { "line": 1, "col": 2, "fragment": "(" } },
+                { "kind": "Int", "span": { "line": 1, "col": 3, "fragment": "1" } },
+                { "kind": "Symbol", "span": { "line": 1, "col": 4, "fragment": ")" } },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_typed() {
+        let content = r#"
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+def main: Int
+  a = 1
+  a
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+
+        let ast = apply(&file.unit, None);
+        assert_eq!(ast[2]["exprs"][1], json!({ "kind": "Identifier", "name": { "line": 9, "col": 3, "fragment": "a" }, "source": null }));
+
+        let root = index::build(&[file.deref()]);
+        analyse::apply(&mut [file.deref_mut()], &root);
+        let mut scope = Scope { levels: vec![] };
+        scope.enter_root(&root);
+
+        let typed_ast = apply(&file.unit, Some(&scope));
+        assert_eq!(typed_ast[2]["return_type"]["class"], json!("Int"));
+        assert_eq!(typed_ast[2]["exprs"][0]["type"], json!("Int"));
+        assert_eq!(typed_ast[2]["exprs"][0]["expr"]["instance"]["class"], json!("Int"));
+        assert_eq!(
+            typed_ast[2]["exprs"][1]["source"],
+            json!({ "kind": "Assignment", "name": { "line": 8, "col": 3, "fragment": "a" } })
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use std::ffi::CString;
+use std::mem;
+use std::os::raw::{c_char, c_void};
+
+use inkwell::builder::Builder;
+use inkwell::types::BasicTypeEnum;
+use inkwell::values::{AsValueRef, FunctionValue, PointerValue};
+use tokenize::span::Span;
+
+// The DWARF encodings of the variables, from the DWARF spec.
+const DW_ATE_BOOLEAN: u32 = 0x02;
+const DW_ATE_FLOAT: u32 = 0x04;
+const DW_ATE_SIGNED: u32 = 0x05;
+// A pointer, e.g. to the struct of an instance, which isn't a DWARF encoding.
+const POINTER: u32 = 0;
+
+// Implemented by native/debug_info.cpp over the C++ DIBuilder, because the C API of LLVM 6 can't create subprograms
+// or variables.
+#[cfg(feature = "debug-info")]
+extern "C" {
+    fn lilit_debug_info_create(path: *const c_char) -> *mut c_void;
+    fn lilit_debug_info_create_subprogram(info: *mut c_void, function: *mut c_void, name: *const c_char, path: *const c_char, line: u32);
+    fn lilit_debug_info_set_location(builder: *mut c_void, line: u32, col: u32) -> *mut c_void;
+    fn lilit_debug_info_restore_location(builder: *mut c_void, previous: *mut c_void);
+    fn lilit_debug_info_declare_variable(
+        info: *mut c_void,
+        alloca: *mut c_void,
+        name: *const c_char,
+        path: *const c_char,
+        line: u32,
+        col: u32,
+        type_name: *const c_char,
+        size_in_bits: u32,
+        encoding: u32,
+    );
+    fn lilit_debug_info_finalize_subprogram(info: *mut c_void, function: *mut c_void);
+    fn lilit_debug_info_finalize(info: *mut c_void);
+}
+
+// Without the debug-info feature, native/debug_info.cpp isn't built and -g is rejected, so none of these runs.
+#[cfg(not(feature = "debug-info"))]
+use self::unsupported::*;
+
+#[cfg(not(feature = "debug-info"))]
+#[allow(unused_variables, clippy::too_many_arguments)]
+mod unsupported {
+    use std::os::raw::{c_char, c_void};
+
+    const MESSAGE: &str = "lilit is built without the debug-info feature";
+
+    pub unsafe fn lilit_debug_info_create(path: *const c_char) -> *mut c_void { unreachable!("{}", MESSAGE) }
+    pub unsafe fn lilit_debug_info_create_subprogram(info: *mut c_void, function: *mut c_void, name: *const c_char, path: *const c_char, line: u32) { unreachable!("{}", MESSAGE) }
+    pub unsafe fn lilit_debug_info_set_location(builder: *mut c_void, line: u32, col: u32) -> *mut c_void { unreachable!("{}", MESSAGE) }
+    pub unsafe fn lilit_debug_info_restore_location(builder: *mut c_void, previous: *mut c_void) { unreachable!("{}", MESSAGE) }
+    pub unsafe fn lilit_debug_info_declare_variable(
+        info: *mut c_void,
+        alloca: *mut c_void,
+        name: *const c_char,
+        path: *const c_char,
+        line: u32,
+        col: u32,
+        type_name: *const c_char,
+        size_in_bits: u32,
+        encoding: u32,
+    ) { unreachable!("{}", MESSAGE) }
+    pub unsafe fn lilit_debug_info_finalize_subprogram(info: *mut c_void, function: *mut c_void) { unreachable!("{}", MESSAGE) }
+    pub unsafe fn lilit_debug_info_finalize(info: *mut c_void) { unreachable!("{}", MESSAGE) }
+}
+
+// The DWARF debug info of a module for `-g`. Only the methods in the Lilit source have a subprogram, so the functions
+// that the compiler generates, e.g. the constructors, are stepped over in gdb.
+pub struct DebugInfo {
+    raw: *mut c_void,
+    pointer_size_in_bits: u32,
+}
+
+impl DebugInfo {
+    // The path is the file of the compile unit, i.e. the file that is compiled.
+    pub fn new(path: &str, pointer_size_in_bits: u32) -> DebugInfo {
+        let path = CString::new(path).unwrap();
+        DebugInfo { raw: unsafe { lilit_debug_info_create(path.as_ptr()) }, pointer_size_in_bits }
+    }
+
+    pub fn create_subprogram(&self, function: FunctionValue, name: Span) {
+        let fragment = CString::new(name.fragment).unwrap();
+        let path = get_path(name);
+        unsafe {
+            lilit_debug_info_create_subprogram(self.raw, function.as_value_ref() as *mut c_void, fragment.as_ptr(), path.as_ptr(), name.line as u32);
+        }
+    }
+
+    // What the builder emits is located at the span until the returned location, which is the previous one, is restored.
+    pub fn set_location(&self, builder: &Builder, span: Span) -> Location {
+        Location(unsafe { lilit_debug_info_set_location(get_builder_ref(builder), span.line as u32, span.col as u32) })
+    }
+
+    pub fn restore_location(&self, builder: &Builder, previous: Location) {
+        unsafe { lilit_debug_info_restore_location(get_builder_ref(builder), previous.0) };
+    }
+
+    // The type is the LLVM type of the class, e.g. i64 for Int, and the type name is the name of the class.
+    pub fn declare_variable(&self, alloca: PointerValue, name: Span, type_name: &str, tpe: BasicTypeEnum) {
+        let (size_in_bits, encoding) = match tpe {
+            BasicTypeEnum::IntType(t) if t.get_bit_width() == 1 => (8, DW_ATE_BOOLEAN),
+            BasicTypeEnum::IntType(t) => (t.get_bit_width(), DW_ATE_SIGNED),
+            BasicTypeEnum::FloatType(_) => (64, DW_ATE_FLOAT),
+            _ => (self.pointer_size_in_bits, POINTER),
+        };
+        let fragment = CString::new(name.fragment).unwrap();
+        let path = get_path(name);
+        let type_name = CString::new(type_name).unwrap();
+
+        unsafe {
+            lilit_debug_info_declare_variable(
+                self.raw,
+                alloca.as_value_ref() as *mut c_void,
+                fragment.as_ptr(),
+                path.as_ptr(),
+                name.line as u32,
+                name.col as u32,
+                type_name.as_ptr(),
+                size_in_bits,
+                encoding,
+            );
+        }
+    }
+
+    // Runs after the last variable of the function is declared, and before the function is verified.
+    pub fn finalize_subprogram(&self, function: FunctionValue) {
+        unsafe { lilit_debug_info_finalize_subprogram(self.raw, function.as_value_ref() as *mut c_void) };
+    }
+
+    // The module only has the debug info after it's finalized, so this runs before the module is optimized or written.
+    pub fn finalize(self) {
+        unsafe { lilit_debug_info_finalize(self.raw) };
+    }
+}
+
+// A location of the builder, which is null when nothing is located, e.g. before the first method.
+pub struct Location(*mut c_void);
+
+// inkwell doesn't expose the LLVMBuilderRef of a builder, which is its only field.
+fn get_builder_ref(builder: &Builder) -> *mut c_void {
+    assert_eq!(mem::size_of::<Builder>(), mem::size_of::<*mut c_void>(), "The builder isn't only an LLVMBuilderRef");
+    unsafe { *(builder as *const Builder as *const *mut c_void) }
+}
+
+fn get_path(span: Span) -> CString {
+    CString::new(unsafe { &*span.file }.path.as_str()).unwrap()
+}
@@ -8,6 +8,7 @@ use emit::def::method::EmitterMethod;
 pub trait ClassEmitter {
     fn apply_class_def(&self, class: &Class);
     fn apply_class(&self, class: &Class);
+    fn apply_class_methods(&self, class: &Class);
     fn get_type_enums_for_class(&self, class: &Class) -> Vec<BasicTypeEnum>;
     fn get_type_enums_for_native(&self, class: &Class) -> Vec<BasicTypeEnum>;
 }
@@ -38,6 +39,12 @@ impl ClassEmitter for Emitter<'_> {
             class.llvm.get().unwrap().set_body(&self.get_type_enums_for_class(class), false);
         }
 
+        for method in &class.methods {
+            self.apply_method_def(method);
+        }
+    }
+
+    fn apply_class_methods(&self, class: &Class) {
         for method in &class.methods {
             self.apply_method(method);
         }
@@ -45,9 +52,9 @@ impl ClassEmitter for Emitter<'_> {
 
     fn get_type_enums_for_class(&self, class: &Class) -> Vec<BasicTypeEnum> {
         let mut type_enums = vec![];
-        for param in &class.params {
+        for param in class.get_members() {
             let param_class = unsafe { &* param.tpe.class_def.unwrap() };
-            type_enums.push(param_class.llvm.get().unwrap().ptr_type(AddressSpace::Generic).into());
+            type_enums.push(self.get_llvm_type(param_class));
         }
         type_enums
     }
@@ -55,7 +62,8 @@ impl ClassEmitter for Emitter<'_> {
     fn get_type_enums_for_native(&self, class: &Class) -> Vec<BasicTypeEnum> {
        match class.name.fragment {
            "Native__Char" => vec![self.context.i8_type().into()],
-           "Native__Int" => vec![self.context.i64_type().into()],
+           _ if self.get_native_int_type(class).is_some() => vec![self.get_type_for_native(class)],
+           "Native__Float" => vec![self.context.f64_type().into()],
            "Native__String" => vec![self.context.i8_type().ptr_type(AddressSpace::Generic).into()],
            "Native__Void" => vec![],
            "Native__Any" => vec![],
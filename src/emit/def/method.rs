@@ -1,57 +1,66 @@
 use emit::{Emitter, Value};
-use parse::tree::Method;
+use parse::tree::{Method, Expr};
 use inkwell::AddressSpace;
 use inkwell::IntPredicate;
 use inkwell::values::{FunctionValue, BasicValueEnum, InstructionOpcode};
 use emit::expr::ExprEmitter;
-use emit::helper::Helper;
+use emit::helper::{Helper, get_fn_type};
 use inkwell::types::BasicTypeEnum;
 use std::ptr::null;
 
 pub trait EmitterMethod {
+    fn apply_method_def(&self, method: &Method);
     fn apply_method(&self, method: &Method);
+    fn get_method_name(&self, method: &Method) -> String;
     fn create_llvm_main_method(&self, method: &Method);
+    fn apply_native_method_def(&self, method: &Method);
     fn apply_native_method(&self, method: &Method);
 }
 
 impl EmitterMethod for Emitter<'_> {
-    fn apply_method(&self, method: &Method) {
+    fn apply_method_def(&self, method: &Method) {
         if method.name.fragment.starts_with("native__") {
-            self.apply_native_method(method);
+            self.apply_native_method_def(method);
             return;
         }
 
-        let is_main = method.name.fragment == "main";
-        let real_name = if is_main {
-            "native__main".to_string()
-        } else if let Some(parent_class) = method.parent_class {
-            let parent_class = unsafe { &*parent_class };
-            format!("lilit_user_space__{}__{}", parent_class.name.fragment, method.name.fragment)
-        } else {
-            format!("lilit_user_space__{}", method.name.fragment)
-        };
-
         let mut param_types = vec![];
 
         for param in &method.params {
-            param_types.push(param.get_llvm_type());
+            param_types.push(self.get_param_type(param));
         }
 
         let return_type_class = unsafe { &*method.return_type.class_def.unwrap() };
         let llvm_fn_type = if return_type_class.name.fragment == "Void" {
             self.context.void_type().fn_type(&param_types, false)
         } else {
-            return_type_class.llvm.get().unwrap().ptr_type(AddressSpace::Generic).fn_type(&param_types, false)
+            get_fn_type(self.get_llvm_type(return_type_class), &param_types, false)
         };
 
-        let llvm_method = self.module.add_function(&real_name, llvm_fn_type, None);
+        let llvm_method = self.module.add_function(&self.get_method_name(method), llvm_fn_type, None);
         method.llvm.set(Some(llvm_method));
+    }
+
+    fn apply_method(&self, method: &Method) {
+        if method.name.fragment.starts_with("native__") {
+            self.apply_native_method(method);
+            return;
+        }
+
+        let llvm_method = method.llvm.get().unwrap();
+        let return_type_class = unsafe { &*method.return_type.class_def.unwrap() };
 
         let first_block = self.context.append_basic_block(&llvm_method, "first_block");
         self.builder.position_at_end(&first_block);
 
+        // The params, and the return of a method without exprs, are located at the method.
+        let previous_location = self.debug_info.as_ref().map(|debug_info| {
+            debug_info.create_subprogram(llvm_method, method.name);
+            debug_info.set_location(&self.builder, method.name)
+        });
+
         for (index, param) in method.params.iter().enumerate() {
-            let alloca_ptr = self.builder.build_alloca(param.get_llvm_type(), format!("Param {} of method {}", index, method.name.fragment).as_ref());
+            let alloca_ptr = self.builder.build_alloca(self.get_param_type(param), format!("Param {} of method {}", index, method.name.fragment).as_ref());
             self.builder.build_store(alloca_ptr, llvm_method.get_nth_param(index as u32).unwrap());
             param.llvm.set(Some(alloca_ptr));
         }
@@ -59,23 +68,59 @@ impl EmitterMethod for Emitter<'_> {
         for (index, expr) in method.exprs.iter().enumerate() {
             let ret = self.apply_expr(expr);
             if index == (method.exprs.len() - 1) {
-                match return_type_class.name.fragment {
-                    "Void" => self.builder.build_return(None),
+                // The return is located at the last expr.
+                if let (Some(debug_info), Some(span)) = (&self.debug_info, expr.get_span()) {
+                    debug_info.set_location(&self.builder, span);
+                }
+
+                match (return_type_class.name.fragment, expr) {
+                    ("Void", _) => self.builder.build_return(None),
+                    // A method may end with a panic instead of its return value, and the panic never returns.
+                    (_, Expr::Invoke(invoke)) if invoke.is_panic() => self.builder.build_unreachable(),
                     _ => self.builder.build_return(Some(&self.wrap_with_class(&ret, return_type_class)))
                 };
             }
         }
 
+        if method.exprs.is_empty() {
+            match return_type_class.name.fragment {
+                "Void" => self.builder.build_return(None),
+                _ => panic!("The method {} must return {}", method.name.fragment, return_type_class.name.fragment),
+            };
+        }
+
+        if let (Some(debug_info), Some(location)) = (&self.debug_info, previous_location) {
+            debug_info.restore_location(&self.builder, location);
+            debug_info.finalize_subprogram(llvm_method);
+        }
+
         if !llvm_method.verify(true) {
             llvm_method.print_to_stderr();
             panic!("{}(...) is invalid.", method.name.fragment);
         }
 
-        if is_main {
+        if method.name.fragment == "main" {
             self.create_llvm_main_method(method);
         }
     }
 
+    fn get_method_name(&self, method: &Method) -> String {
+        if method.name.fragment == "main" {
+            return "native__main".to_string();
+        }
+
+        match method.parent_class {
+            Some(parent_class) => {
+                let parent_class = unsafe { &*parent_class };
+                match parent_class.find_constructors().iter().position(|c| *c as *const Method == method as *const Method) {
+                    Some(index) => format!("lilit_user_space__{}__{}__constructor{}", parent_class.name.fragment, method.name.fragment, index),
+                    None => format!("lilit_user_space__{}__{}", parent_class.name.fragment, method.name.fragment),
+                }
+            },
+            None => format!("lilit_user_space__{}", method.name.fragment),
+        }
+    }
+
     fn create_llvm_main_method(&self, method: &Method) {
             let fn_type = self.context.i32_type().fn_type(
                 &[
@@ -88,18 +133,24 @@ impl EmitterMethod for Emitter<'_> {
         let main = self.module.add_function("main", fn_type, None);
         let first_block = self.context.append_basic_block(&main, "first_block");
         self.builder.position_at_end(&first_block);
+        self.gc_init();
 
         let llvm_ret = self.builder.build_call(
             method.llvm.get().unwrap(),
             &[],
             &method.name.fragment);
 
+        // The exit code is the native int that the return type unboxes to, e.g. Int.
         let return_type_class = unsafe { &*method.return_type.class_def.unwrap() };
-        let ret_ptr = unwrap!(BasicValueEnum::PointerValue, llvm_ret.try_as_basic_value().left().unwrap());
-        let first_arg_ptr = unsafe { self.builder.build_struct_gep(ret_ptr, 0, "Gep for the first param of Int") };
-        let native_int = unwrap!(BasicValueEnum::PointerValue, self.builder.build_load(first_arg_ptr, "Load the first param of Int"));
-        let native_int_first_arg_ptr = unsafe { self.builder.build_struct_gep(native_int, 0, "Gep for the first param of Native__Int") };
-        let int_value = unwrap!(BasicValueEnum::IntValue, self.builder.build_load(native_int_first_arg_ptr, "Load the first param of Native__Int"));
+        let int_value = match self.get_unboxed_type(return_type_class) {
+            Some(BasicTypeEnum::IntType(_)) => unwrap!(BasicValueEnum::IntValue, llvm_ret.try_as_basic_value().left().unwrap()),
+            _ => panic!(
+                "main must return a class that unboxes to a native int, e.g. Int, but {} doesn't at line {}, column {}",
+                return_type_class.name.fragment,
+                method.name.line,
+                method.name.col
+            ),
+        };
 
         self.builder.build_return(Some(&self.builder.build_int_cast(
             int_value,
@@ -113,7 +164,7 @@ impl EmitterMethod for Emitter<'_> {
         }
     }
 
-    fn apply_native_method<'def>(&self, method: &Method<'def>) {
+    fn apply_native_method_def<'def>(&self, method: &Method<'def>) {
         let mut param_types = vec![];
         for param in &method.params {
             if param.is_varargs { continue; }
@@ -126,8 +177,9 @@ impl EmitterMethod for Emitter<'_> {
         let is_varargs = method.params.last().map(|p|p.is_varargs).unwrap_or(false);
         let llvm_fn_type = match return_type_class.name.fragment {
             "Native__Void" => self.context.void_type().fn_type(&param_types, is_varargs),
-            "Native__Int" => self.context.i64_type().fn_type(&param_types, is_varargs),
+            _ if self.get_native_int_type(return_type_class).is_some() => self.get_native_int_type(return_type_class).unwrap().fn_type(&param_types, is_varargs),
             "Native__Char" => self.context.i8_type().fn_type(&param_types, is_varargs),
+            "Native__Float" => self.context.f64_type().fn_type(&param_types, is_varargs),
             "Native__String" => self.context.i8_type().ptr_type(AddressSpace::Generic).fn_type(&param_types, is_varargs),
             other if other.starts_with("Native__Struct") => return_type_class.llvm_native.get().unwrap().ptr_type(AddressSpace::Generic).fn_type(&param_types, is_varargs),
             other => panic!("Unsupported {}", other)
@@ -135,6 +187,10 @@ impl EmitterMethod for Emitter<'_> {
 
         let llvm_method = self.module.add_function(method.name.fragment, llvm_fn_type, None);
         method.llvm.set(Some(llvm_method));
+    }
+
+    fn apply_native_method<'def>(&self, method: &Method<'def>) {
+        let llvm_method = method.llvm.get().unwrap();
 
         let first_block = self.context.append_basic_block(&llvm_method, "first_block");
         self.builder.position_at_end(&first_block);
@@ -147,9 +203,8 @@ impl EmitterMethod for Emitter<'_> {
             let param_class = unsafe { &*param.tpe.class_def.unwrap() };
             native_params.push(
                 match param_class.name.fragment {
-                    "Native__Int" => self.context.i64_type().into(),
-                    "Native__String" => self.context.i8_type().ptr_type(AddressSpace::Generic).into(),
-                    "Native__Char" => BasicTypeEnum::IntType(self.context.i8_type()),
+                    "Native__String" | "Native__Char" | "Native__Float" => self.get_type_for_native(param_class),
+                    _ if self.get_native_int_type(param_class).is_some() => self.get_type_for_native(param_class),
                     other => panic!("Unrecognized {}", other),
                 }
             );
@@ -159,7 +214,8 @@ impl EmitterMethod for Emitter<'_> {
 
         let mut va_list_ptr = None;
         if method.params.last().map(|p|p.is_varargs).unwrap_or(false) {
-            va_list_ptr = Some(self.builder.build_alloca(self.va_list_struct_type, "va_list"));
+            let va_list_struct_type = self.va_list_struct_type.expect("Varargs of native methods aren't supported on the target, which emit::check_target reports");
+            va_list_ptr = Some(self.builder.build_alloca(va_list_struct_type, "va_list"));
             let varargs_param = method.params.last().unwrap();
             let va_start = self.get_external_func(
                 "llvm.va_start",
@@ -167,7 +223,7 @@ impl EmitterMethod for Emitter<'_> {
             );
             self.builder.build_call(va_start, vec![BasicValueEnum::PointerValue(va_list_ptr.unwrap())].as_ref(), "va_start(va_list)");
 
-            native_params.push(BasicTypeEnum::PointerType(self.va_list_struct_type.ptr_type(AddressSpace::Generic)));
+            native_params.push(BasicTypeEnum::PointerType(va_list_struct_type.ptr_type(AddressSpace::Generic)));
             native_args.push(BasicValueEnum::PointerValue(va_list_ptr.unwrap()));
         }
 
@@ -177,8 +233,9 @@ impl EmitterMethod for Emitter<'_> {
             native_method_name,
             match return_type_class.name.fragment {
                 "Native__Void" => self.context.void_type().fn_type(&native_params, false),
-                "Native__Int" => self.context.i64_type().fn_type(&native_params, false),
+                _ if self.get_native_int_type(return_type_class).is_some() => self.get_native_int_type(return_type_class).unwrap().fn_type(&native_params, false),
                 "Native__Char" => self.context.i8_type().fn_type(&native_params, false),
+                "Native__Float" => self.context.f64_type().fn_type(&native_params, false),
                 other if other.starts_with("Native__Struct") => return_type_class.llvm_native.get().unwrap().ptr_type(AddressSpace::Generic).fn_type(&native_params, false),
                 other => panic!("Unrecognized {}", other),
             }
@@ -4,7 +4,6 @@ use inkwell::values::BasicValueEnum;
 use inkwell::types::BasicTypeEnum;
 use emit::helper::Helper;
 use emit::expr::ExprEmitter;
-use inkwell::AddressSpace;
 
 pub trait AssignmentEmitter {
     fn apply_assignment<'def>(&self, assignment: &Assignment<'def>) -> Value<'def>;
@@ -13,7 +12,11 @@ pub trait AssignmentEmitter {
 impl AssignmentEmitter for Emitter<'_> {
     fn apply_assignment<'def>(&self, assignment: &Assignment<'def>) -> Value<'def> {
         let class = unsafe { &*assignment.tpe.unwrap() };
-        let ptr = self.builder.build_alloca(class.llvm.get().unwrap().ptr_type(AddressSpace::Generic), "alloca assignment");
+        let ptr = self.builder.build_alloca(self.get_llvm_type(class), "alloca assignment");
+
+        if let Some(debug_info) = &self.debug_info {
+            debug_info.declare_variable(ptr, assignment.name, class.name.fragment, self.get_llvm_type(class));
+        }
 
         assignment.llvm.set(Some(ptr));
 
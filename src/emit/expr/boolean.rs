@@ -0,0 +1,13 @@
+use emit::{Emitter, Value};
+use parse::tree::Boolean;
+use emit::expr::new_instance::NewInstanceEmitter;
+
+pub trait BooleanEmitter {
+    fn apply_boolean<'def>(&self, boolean: &Boolean<'def>) -> Value<'def>;
+}
+
+impl BooleanEmitter for Emitter<'_> {
+    fn apply_boolean<'def>(&self, boolean: &Boolean<'def>) -> Value<'def> {
+        self.apply_new_instance(boolean.instance.as_ref().unwrap())
+    }
+}
@@ -0,0 +1,13 @@
+use emit::{Emitter, Value};
+use parse::tree::Float;
+use emit::expr::new_instance::NewInstanceEmitter;
+
+pub trait FloatEmitter {
+    fn apply_float<'def>(&self, float: &Float<'def>) -> Value<'def>;
+}
+
+impl FloatEmitter for Emitter<'_> {
+    fn apply_float<'def>(&self, float: &Float<'def>) -> Value<'def> {
+        self.apply_new_instance(float.instance.as_ref().unwrap())
+    }
+}
@@ -0,0 +1,96 @@
+use emit::{Emitter, Value};
+use parse::tree::{Interpolation, InterpolationPart};
+use emit::expr::ExprEmitter;
+use emit::expr::literal_string::LiteralStringEmitter;
+use emit::expr::new_instance::NewInstanceEmitter;
+use emit::helper::Helper;
+use inkwell::values::{BasicValueEnum, PointerValue};
+use inkwell::AddressSpace;
+
+pub trait InterpolationEmitter {
+    fn apply_interpolation<'def>(&self, interpolation: &Interpolation<'def>) -> Value<'def>;
+}
+
+impl InterpolationEmitter for Emitter<'_> {
+    fn apply_interpolation<'def>(&self, interpolation: &Interpolation<'def>) -> Value<'def> {
+        let string_class = unsafe { &*interpolation.class_def.unwrap() };
+        let native_string_class = unsafe { &*string_class.params.get(0).unwrap().tpe.class_def.unwrap() };
+
+        let i8_ptr_type = self.context.i8_type().ptr_type(AddressSpace::Generic);
+        let concat = self.get_external_func(
+            "lilit_string_concat",
+            i8_ptr_type.fn_type(&[i8_ptr_type.into(), i8_ptr_type.into()], false)
+        );
+
+        let mut result: Option<PointerValue> = None;
+
+        for part in &interpolation.parts {
+            let value = match part {
+                InterpolationPart::Text(text) => self.apply_literal_string(text),
+                InterpolationPart::Expr(e) => self.apply_expr(e),
+            };
+            let (string, class) = unwrap2!(Value::Class, value);
+            let native = unwrap!(BasicValueEnum::PointerValue, self.get_underlying(string, unsafe { &*class }));
+
+            result = Some(match result {
+                None => native,
+                Some(previous) => {
+                    let ret = self.builder.build_call(concat, &[previous.into(), native.into()], "Concat the interpolated strings");
+                    unwrap!(BasicValueEnum::PointerValue, ret.try_as_basic_value().left().unwrap())
+                },
+            });
+        }
+
+        let native_string = self.wrap_with_class(&Value::String(result.unwrap()), native_string_class);
+
+        Value::Class(
+            self.alloc_new_instance(string_class, vec![Value::Class(native_string, native_string_class)]),
+            string_class
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::{Deref, DerefMut};
+
+    use index::build;
+    use ::{parse, analyse};
+    use emit::apply;
+
+    #[test]
+    fn test_full() {
+        let content = r#"
+class Void
+end
+
+class Native__String
+end
+
+class String(underlying: Native__String)
+end
+
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+  def to_s(): String
+    "int"
+  end
+end
+
+def main(): Int
+  name = "world"
+  "Hello #{name}! #{1}"
+  0
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
+
+        let module = apply(&[file.deref()]);
+        module.print_to_stderr();
+    }
+}
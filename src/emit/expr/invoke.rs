@@ -2,16 +2,25 @@ use parse::tree::Invoke;
 use emit::{Value, Emitter};
 use emit::expr::ExprEmitter;
 use inkwell::values::BasicValueEnum;
-use inkwell::AddressSpace;
-use emit::helper::Helper;
+use inkwell::{AddressSpace, IntPredicate};
+use emit::helper::{Helper, is_unsigned_native_int};
+
+// The divisor of these natives is checked before the call, so dividing by zero panics at the invoke instead of
+// crashing with SIGFPE.
+const CHECKED_DIVISIONS: [&str; 2] = ["native__lilit_int_div", "native__lilit_int_mod"];
 
 pub trait InvokeEmitter {
     fn apply_invoke<'def>(&self, invoke: &Invoke<'def>) -> Value<'def>;
     fn apply_native_invoke<'def>(&self, invoke: &Invoke<'def>) -> Value<'def>;
+    fn apply_panic<'def>(&self, invoke: &Invoke<'def>) -> Value<'def>;
 }
 
 impl InvokeEmitter for Emitter<'_> {
     fn apply_invoke<'def>(&self, invoke: &Invoke<'def>) -> Value<'def> {
+        if invoke.is_panic() {
+            return self.apply_panic(invoke);
+        }
+
         if invoke.name.fragment.starts_with("native__") {
             return self.apply_native_invoke(invoke);
         }
@@ -21,12 +30,12 @@ impl InvokeEmitter for Emitter<'_> {
 
         if let Some(parent) = &invoke.invoker_opt {
             let (parent, _) = unwrap2!(Value::Class, self.apply_expr(parent));
-            args.push(BasicValueEnum::PointerValue(parent));
+            args.push(parent);
         }
 
         for (param, arg) in method.params.iter().zip(&invoke.args) {
-            let (ptr, arg_class) = unwrap2!(Value::Class, self.apply_expr(arg));
-            args.push(BasicValueEnum::PointerValue(ptr));
+            let (value, _) = unwrap2!(Value::Class, self.apply_expr(arg));
+            args.push(value);
         }
 
         let llvm_ret = self.builder.build_call(
@@ -38,7 +47,7 @@ impl InvokeEmitter for Emitter<'_> {
 
         match return_type_class.name.fragment {
             "Void"  => Value::Void,
-            other => Value::Class(unwrap!(BasicValueEnum::PointerValue, llvm_ret.try_as_basic_value().left().unwrap()), return_type_class),
+            _ => Value::Class(llvm_ret.try_as_basic_value().left().unwrap(), return_type_class),
         }
     }
 
@@ -54,7 +63,7 @@ impl InvokeEmitter for Emitter<'_> {
                 assert!(method.params.last().unwrap().is_varargs, "The last param's varargs isn't true");
                 method.params.last().unwrap()
             };
-            let (ptr, arg_class) = unwrap2!(Value::Class, self.apply_expr(arg));
+            let (value, arg_class) = unwrap2!(Value::Class, self.apply_expr(arg));
 
             let arg_class = unsafe { &*arg_class };
             assert!(arg_class.name.fragment.starts_with("Native__"), "Expect {} to be a native class", arg_class.name.fragment);
@@ -68,14 +77,38 @@ impl InvokeEmitter for Emitter<'_> {
                         "Native__String" => BasicValueEnum::PointerValue(self.context.i8_type().ptr_type(AddressSpace::Generic).const_null()),
                         other => panic!("Null only works with Native__String, not {}", other)
                     },
-                    _ => {
-                        let first_arg_ptr = unsafe { self.builder.build_struct_gep(ptr, 0, "Gep the native value") };
-                        self.builder.build_load(first_arg_ptr, "Load the native value")
-                    }
+                    _ => match self.get_native_int_type(arg_class) {
+                        // C promotes varargs narrower than int, so the callee reads at least 32 bits.
+                        Some(int_type) if param.is_varargs && int_type.get_bit_width() < 32 => {
+                            let value = unwrap!(BasicValueEnum::IntValue, value);
+                            BasicValueEnum::IntValue(if is_unsigned_native_int(arg_class) {
+                                self.builder.build_int_z_extend(value, self.context.i32_type(), "Promote the vararg")
+                            } else {
+                                self.builder.build_int_s_extend(value, self.context.i32_type(), "Promote the vararg")
+                            })
+                        },
+                        _ => value,
+                    },
                 }
             );
         }
 
+        if CHECKED_DIVISIONS.contains(&invoke.name.fragment) {
+            let dividend = unwrap!(BasicValueEnum::IntValue, args[0]);
+            let divisor = unwrap!(BasicValueEnum::IntValue, args[1]);
+            let int_type = divisor.get_type();
+
+            let is_zero = self.builder.build_int_compare(IntPredicate::EQ, divisor, int_type.const_int(0, false), "Check the divisor");
+            self.build_panic_if(is_zero, "Division by zero", invoke.name);
+
+            // The quotient of the smallest Int by -1 doesn't fit, but the remainder is 0, which lilit_int_mod returns.
+            if invoke.name.fragment == "native__lilit_int_div" {
+                let is_min = self.builder.build_int_compare(IntPredicate::EQ, dividend, int_type.const_int(i64::min_value() as u64, false), "Check the dividend");
+                let is_minus_one = self.builder.build_int_compare(IntPredicate::EQ, divisor, int_type.const_int(-1i64 as u64, true), "Check the divisor");
+                self.build_panic_if(self.builder.build_and(is_min, is_minus_one, "Check the overflow"), "Division overflow", invoke.name);
+            }
+        }
+
         let llvm_ret = self.builder.build_call(
             method.llvm.get().unwrap(),
             &args,
@@ -85,10 +118,27 @@ impl InvokeEmitter for Emitter<'_> {
         match return_type_class.name.fragment {
             "Native__Void" => Value::Void,
             _ => {
-                Value::Class(self.wrap_with_class(&self.to_value(llvm_ret.try_as_basic_value().left().unwrap(), return_type_class), return_type_class), return_type_class)
+                let ret = llvm_ret.try_as_basic_value().left().unwrap();
+
+                // A native call that fails returns a null struct, e.g. lilit_exec when it's unable to fork.
+                if return_type_class.name.fragment.starts_with("Native__Struct__") {
+                    let is_null = self.builder.build_is_null(unwrap!(BasicValueEnum::PointerValue, ret), "Check the native struct");
+                    self.build_panic_if(is_null, &format!("{} failed", invoke.name.fragment), invoke.name);
+                }
+
+                Value::Class(self.wrap_with_class(&self.to_value(ret, return_type_class), return_type_class), return_type_class)
             }
         }
     }
+
+    // The message is a String, whose native string is passed to the runtime.
+    fn apply_panic<'def>(&self, invoke: &Invoke<'def>) -> Value<'def> {
+        let (message, class) = unwrap2!(Value::Class, self.apply_expr(&invoke.args[0]));
+        let message = self.get_underlying(message, unsafe { &*class });
+        self.build_panic(unwrap!(BasicValueEnum::PointerValue, message), invoke.name);
+
+        Value::Void
+    }
 }
 
 #[cfg(test)]
@@ -100,7 +150,110 @@ mod tests {
     use parse::tree::{CompilationUnit, Type, CompilationUnitItem, Method, Invoke, Expr, Int, NewInstance, NativeInt};
     use test_common::span2;
     use std::cell::{Cell, RefCell};
+    use std::env;
+    use std::ffi::CStr;
+    use std::os::raw::c_char;
+    use std::process::{self, Command};
+    use std::ptr::null;
     use emit::apply;
+    use inkwell::targets::{InitializationConfig, Target};
+    use inkwell::OptimizationLevel;
+
+    // Prints what lilit_panic in native/lib.c prints, without the backtrace, and exits with the same status.
+    extern "C" fn lilit_panic(message: *const c_char, file: *const c_char, line: i64, col: i64) {
+        unsafe {
+            eprintln!("panic: {}", CStr::from_ptr(message).to_str().unwrap());
+            eprintln!("  at {}:{}:{}", CStr::from_ptr(file).to_str().unwrap(), line, col);
+        }
+        process::exit(101);
+    }
+
+    extern "C" fn lilit_gc_init() {}
+
+    extern "C" fn lilit_int_sub(a: i64, b: i64) -> i64 {
+        a.wrapping_sub(b)
+    }
+
+    extern "C" fn lilit_int_div(a: i64, b: i64) -> i64 {
+        a / b
+    }
+
+    const DIVISION: &str = r#"
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+  def sub(other: Int): Int
+    Int(native__lilit_int_sub(underlying, other.underlying))
+  end
+
+  def div(other: Int): Int
+    Int(native__lilit_int_div(underlying, other.underlying))
+  end
+end
+
+def native__lilit_int_sub(a: Native__Int, b: Native__Int): Native__Int
+end
+
+def native__lilit_int_div(a: Native__Int, b: Native__Int): Native__Int
+end
+    "#;
+
+    // The panic exits the process, so main runs in a child, which is the same test run again, and the test checks what
+    // the child prints.
+    fn assert_panic(test_name: &str, main: &str, expected: &str) {
+        let content = format!("{}\n{}", DIVISION.trim(), main.trim());
+
+        if env::var("LILIT_PANIC_TEST").ok().as_ref().map(String::as_str) == Some(test_name) {
+            let mut file = unwrap!(Ok, parse::apply(&content, "division.lilit"));
+            let root = build(&[file.deref()]);
+            analyse::apply(&mut [file.deref_mut()], &root);
+            let module = apply(&[file.deref()]);
+
+            Target::initialize_native(&InitializationConfig::default()).unwrap();
+            let engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+            engine.add_global_mapping(&module.get_function("lilit_panic").unwrap(), lilit_panic as usize);
+            engine.add_global_mapping(&module.get_function("lilit_gc_init").unwrap(), lilit_gc_init as usize);
+            engine.add_global_mapping(&module.get_function("lilit_int_sub").unwrap(), lilit_int_sub as usize);
+            engine.add_global_mapping(&module.get_function("lilit_int_div").unwrap(), lilit_int_div as usize);
+
+            unsafe {
+                let main = engine.get_function::<unsafe extern "C" fn(i32, *const *const i8) -> i32>("main").unwrap();
+                main.call(0, null());
+            }
+            panic!("main returns without a panic");
+        }
+
+        let output = Command::new(env::current_exe().unwrap())
+            .args(&[&format!("emit::expr::invoke::tests::{}", test_name), "--exact", "--nocapture"])
+            .env("LILIT_PANIC_TEST", test_name)
+            .output()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(101));
+        assert!(String::from_utf8_lossy(&output.stderr).contains(expected), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    #[test]
+    fn test_division_by_zero() {
+        let main = r#"
+def main: Int
+  7.div(0)
+end
+        "#;
+        assert_panic("test_division_by_zero", main, "panic: Division by zero\n  at division.lilit:10:9\n");
+    }
+
+    #[test]
+    fn test_division_overflow() {
+        let main = r#"
+def main: Int
+  min = 0.sub(9223372036854775807).sub(1)
+  min.div(0.sub(1))
+end
+        "#;
+        assert_panic("test_division_overflow", main, "panic: Division overflow\n  at division.lilit:10:9\n");
+    }
 
     #[test]
     fn test_full() {
@@ -142,4 +295,86 @@ end
         let module = apply(&[file.deref()]);
         module.print_to_stderr();
     }
+
+    #[test]
+    fn test_static() {
+        let content = r#"
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+class Void
+end
+
+class Config(value: Int)
+  static def parse(value: Int): Config
+    Config(value)
+  end
+end
+
+def main(): Int
+  Config.parse(1).value
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
+
+        let module = apply(&[file.deref()]);
+        module.print_to_stderr();
+    }
+
+    #[test]
+    fn test_panic() {
+        let content = r#"
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+class Native__String
+end
+
+class String(underlying: Native__String)
+end
+
+class Void
+end
+
+class Native__Struct__Process(pid: Native__Int)
+end
+
+def native__lilit_exec(cmd: Native__String): Native__Struct__Process
+end
+
+def native__lilit_int_div(a: Native__Int, b: Native__Int): Native__Int
+end
+
+def half(a: Int): Int
+  Int(native__lilit_int_div(a.underlying, 2.underlying))
+end
+
+def fail(): Int
+  panic("Unable to continue")
+end
+
+def main(): Int
+  native__lilit_exec("ls".underlying)
+  half(4)
+  fail()
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
+
+        let module = apply(&[file.deref()]);
+        assert!(module.verify().is_ok());
+        module.print_to_stderr();
+    }
 }
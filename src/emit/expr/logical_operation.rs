@@ -0,0 +1,81 @@
+use emit::{Emitter, Value};
+use emit::expr::ExprEmitter;
+use parse::tree::LogicalOperation;
+use inkwell::values::BasicValueEnum;
+use emit::helper::Helper;
+
+pub trait LogicalOperationEmitter {
+    fn apply_logical_operation<'def>(&self, operation: &LogicalOperation<'def>) -> Value<'def>;
+}
+
+impl LogicalOperationEmitter for Emitter<'_> {
+    // The result is the operand that decides it, so the right side is skipped when the left side already does.
+    fn apply_logical_operation<'def>(&self, operation: &LogicalOperation<'def>) -> Value<'def> {
+        let (left, class) = unwrap2!(Value::Class, self.apply_expr(&operation.left));
+        let left_block = self.builder.get_insert_block().unwrap();
+        let function = left_block.get_parent().unwrap();
+
+        let right_block = self.context.append_basic_block(&function, "logical_right");
+        let end_block = self.context.append_basic_block(&function, "logical_end");
+
+        // Boolean is unboxed, so it's the i1 itself.
+        let condition = unwrap!(BasicValueEnum::IntValue, left);
+        match operation.operator.fragment {
+            "&&" => self.builder.build_conditional_branch(condition, &right_block, &end_block),
+            "||" => self.builder.build_conditional_branch(condition, &end_block, &right_block),
+            other => panic!("Unsupported logical operator {}", other),
+        };
+
+        self.builder.position_at_end(&right_block);
+        let (right, _) = unwrap2!(Value::Class, self.apply_expr(&operation.right));
+        let right_block = self.builder.get_insert_block().unwrap();
+        self.builder.build_unconditional_branch(&end_block);
+
+        self.builder.position_at_end(&end_block);
+        let result = self.builder.build_phi(self.get_llvm_type(unsafe { &*class }), "logical_result");
+        result.add_incoming(&[(&left, &left_block), (&right, &right_block)]);
+
+        Value::Class(result.as_basic_value(), class)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::{Deref, DerefMut};
+
+    use index::build;
+    use ::{parse, analyse};
+    use emit::apply;
+
+    #[test]
+    fn test_short_circuit() {
+        let content = r#"
+class Native__Bool
+end
+
+class Boolean(underlying: Native__Bool)
+end
+
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+def native__lilit_int_eq(a: Native__Int, b: Native__Int): Native__Bool
+end
+
+def main: Boolean
+  a = 1
+  false || Boolean(native__lilit_int_eq(a.underlying, 1.underlying)) && true
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
+
+        let module = apply(&[file.deref()]);
+        module.print_to_stderr();
+    }
+}
@@ -1,33 +1,103 @@
 use emit::{Emitter, Value};
-use parse::tree::MemberAccess;
+use parse::tree::{Class, MemberAccess, MemberAssignment};
 use emit::expr::ExprEmitter;
-use inkwell::values::BasicValueEnum;
+use inkwell::values::{BasicValueEnum, PointerValue};
+use emit::helper::Helper;
 
 pub trait MemberAccessEmitter {
     fn apply_member_access<'def>(&self, member_access: &MemberAccess<'def>) -> Value<'def>;
+    fn apply_member_assignment<'def>(&self, member_assignment: &MemberAssignment<'def>) -> Value<'def>;
+    fn get_member_ptr<'def>(&self, parent: BasicValueEnum, parent_class: &Class<'def>, member_access: &MemberAccess<'def>) -> PointerValue;
 }
 
 impl MemberAccessEmitter for Emitter<'_> {
     fn apply_member_access<'def>(&self, member_access: &MemberAccess<'def>) -> Value<'def> {
-        let (parent_ptr, parent_class)  = unwrap2!(Value::Class, self.apply_expr(&member_access.parent));
+        let param = unsafe { &*member_access.param_def.unwrap() };
+        let param_class = unsafe { &*param.tpe.class_def.unwrap() };
 
+        let (parent, parent_class) = unwrap2!(Value::Class, self.apply_expr(&member_access.parent));
         let parent_class = unsafe { &*parent_class };
-        let param = unsafe { &*member_access.param_def.unwrap() };
+
+        // An unboxed instance is the value of its only param, e.g. `a.underlying` is `a` itself.
+        if self.get_unboxed_type(parent_class).is_some() {
+            return Value::Class(parent, param_class);
+        }
+
+        let param_ptr = self.get_member_ptr(parent, parent_class, member_access);
+
+        let value = self.builder.build_load(
+            param_ptr,
+            &format!("Load field {} for identifier {}", param.name.map(|x|x.fragment).unwrap_or("-"), member_access.name.map(|x|x.fragment).unwrap_or("-"))
+            );
+
+        Value::Class(value, param_class)
+    }
+
+    fn apply_member_assignment<'def>(&self, member_assignment: &MemberAssignment<'def>) -> Value<'def> {
+        let param = unsafe { &*member_assignment.member.param_def.unwrap() };
         let param_class = unsafe { &*param.tpe.class_def.unwrap() };
 
-        let param_ptr = unsafe {
+        let (parent, parent_class) = unwrap2!(Value::Class, self.apply_expr(&member_assignment.member.parent));
+        let parent_class = unsafe { &*parent_class };
+
+        let param_ptr = self.get_member_ptr(parent, parent_class, &member_assignment.member);
+        let value = self.apply_expr(&member_assignment.expr);
+        let value = self.wrap_with_class(&value, param_class);
+
+        self.builder.build_store(param_ptr, value);
+
+        Value::Class(value, param_class)
+    }
+
+    fn get_member_ptr<'def>(&self, parent: BasicValueEnum, parent_class: &Class<'def>, member_access: &MemberAccess<'def>) -> PointerValue {
+        let param = unsafe { &*member_access.param_def.unwrap() };
+
+        unsafe {
             self.builder.build_struct_gep(
-                parent_ptr,
+                unwrap!(BasicValueEnum::PointerValue, parent),
                 param.index as u32,
                 format!("Gep field {} of {}", param.index, parent_class.name.fragment).as_ref()
             )
-        };
+        }
+    }
+}
 
-        let value = self.builder.build_load(
-            param_ptr,
-            &format!("Load field {} for identifier {}", param.name.map(|x|x.fragment).unwrap_or("-"), member_access.name.map(|x|x.fragment).unwrap_or("-"))
-            );
+#[cfg(test)]
+mod tests {
+    use std::ops::{Deref, DerefMut};
+
+    use index::build;
+    use ::{parse, analyse};
+    use emit::apply;
+
+    #[test]
+    fn test_full() {
+        let content = r#"
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+class Void
+end
+
+class Counter(start: Int)
+  var count: Int = 0
+end
+
+def main(): Int
+  counter = Counter(1)
+  counter.count = counter.start
+  counter.count
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
 
-        Value::Class(unwrap!(BasicValueEnum::PointerValue, value), param_class)
+        let module = apply(&[file.deref()]);
+        module.print_to_stderr();
     }
 }
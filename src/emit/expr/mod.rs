@@ -1,5 +1,7 @@
 use emit::{Emitter, Value};
 use parse::tree::Expr;
+use emit::expr::boolean::BooleanEmitter;
+use emit::expr::float::FloatEmitter;
 use emit::expr::int::IntEmitter;
 use emit::expr::native_int::NativeIntEmitter;
 use emit::expr::new_instance::NewInstanceEmitter;
@@ -9,20 +11,34 @@ use emit::expr::literal_string::LiteralStringEmitter;
 use emit::expr::native_string::NativeStringEmitter;
 use emit::expr::invoke::InvokeEmitter;
 use emit::expr::literal_char::LiteralCharEmitter;
+use emit::expr::logical_operation::LogicalOperationEmitter;
+use emit::expr::native_bool::NativeBoolEmitter;
 use emit::expr::native_char::NativeCharEmitter;
+use emit::expr::native_float::NativeFloatEmitter;
 use emit::expr::assignment::AssignmentEmitter;
+use emit::expr::self_instance::SelfInstanceEmitter;
+use emit::expr::interpolation::InterpolationEmitter;
+use emit::expr::using::UsingEmitter;
 
 pub mod assignment;
+pub mod boolean;
+pub mod float;
 pub mod identifier;
 pub mod int;
+pub mod interpolation;
 pub mod invoke;
 pub mod literal_string;
 pub mod literal_char;
+pub mod logical_operation;
 pub mod member_access;
+pub mod native_bool;
 pub mod native_char;
+pub mod native_float;
 pub mod native_int;
 pub mod native_string;
 pub mod new_instance;
+pub mod self_instance;
+pub mod using;
 
 pub trait ExprEmitter {
     fn apply_expr<'def>(&self, expr: &Expr<'def>) -> Value<'def>;
@@ -30,18 +46,38 @@ pub trait ExprEmitter {
 
 impl ExprEmitter for Emitter<'_> {
     fn apply_expr<'def>(&self, expr: &Expr<'def>) -> Value<'def> {
-        match expr {
+        let previous_location = match (&self.debug_info, expr.get_span()) {
+            (Some(debug_info), Some(span)) => Some(debug_info.set_location(&self.builder, span)),
+            _ => None,
+        };
+
+        let value = match expr {
             Expr::Assignment(i) => self.apply_assignment(i),
+            Expr::Boolean(i) => self.apply_boolean(i),
             Expr::Char(i) => self.apply_literal_char(i),
+            Expr::Float(i) => self.apply_float(i),
             Expr::Identifier(i) => self.apply_identifier(i),
             Expr::Int(i) => self.apply_int(i),
+            Expr::Interpolation(i) => self.apply_interpolation(i),
             Expr::Invoke(i) => self.apply_invoke(i),
+            Expr::LogicalOperation(i) => self.apply_logical_operation(i),
             Expr::MemberAccess(i) => self.apply_member_access(i),
+            Expr::MemberAssignment(i) => self.apply_member_assignment(i),
+            Expr::NativeBool(i) => self.apply_native_bool(i),
             Expr::NativeChar(i) => self.apply_native_char(i),
+            Expr::NativeFloat(i) => self.apply_native_float(i),
             Expr::NativeInt(i) => self.apply_native_int(i),
             Expr::NativeString(i) => self.apply_native_string(i),
             Expr::NewInstance(i) => self.apply_new_instance(i),
+            Expr::SelfInstance(i) => self.apply_self_instance(i),
             Expr::String(i) => self.apply_literal_string(i),
+            Expr::Using(i) => self.apply_using(i),
+        };
+
+        if let (Some(debug_info), Some(location)) = (&self.debug_info, previous_location) {
+            debug_info.restore_location(&self.builder, location);
         }
+
+        value
     }
 }
\ No newline at end of file
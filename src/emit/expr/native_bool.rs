@@ -0,0 +1,12 @@
+use emit::{Emitter, Value};
+use parse::tree::NativeBool;
+
+pub trait NativeBoolEmitter {
+    fn apply_native_bool<'def>(&self, boolean: &NativeBool) -> Value<'def>;
+}
+
+impl NativeBoolEmitter for Emitter<'_> {
+    fn apply_native_bool<'def>(&self, boolean: &NativeBool) -> Value<'def> {
+        Value::Int(self.context.bool_type().const_int(boolean.value as u64, false))
+    }
+}
@@ -0,0 +1,12 @@
+use emit::{Emitter, Value};
+use parse::tree::NativeFloat;
+
+pub trait NativeFloatEmitter {
+    fn apply_native_float<'def>(&self, float: &NativeFloat) -> Value<'def>;
+}
+
+impl NativeFloatEmitter for Emitter<'_> {
+    fn apply_native_float<'def>(&self, float: &NativeFloat) -> Value<'def> {
+        Value::Float(self.context.f64_type().const_float(float.value))
+    }
+}
@@ -1,40 +1,82 @@
 use parse::tree::NativeString;
 use emit::{Value, Emitter};
 use emit::helper::Helper;
-use inkwell::AddressSpace;
 
 pub trait NativeStringEmitter {
     fn apply_native_string<'def>(&self, string: &NativeString) -> Value<'def>;
 }
 
 impl NativeStringEmitter for Emitter<'_> {
+    // A literal is a private constant in the module, which every use of the same text shares, so it's never
+    // rebuilt, e.g. in a loop. It's read-only, so a native method that modifies a string must be given a copy from
+    // `native__lilit_string_copy`.
     fn apply_native_string<'def>(&self, string: &NativeString) -> Value<'def> {
-        let i8_type = self.context.i8_type();
-        let i32_type = self.context.i32_type();
-
-        let array_type = i8_type.array_type((string.value.len() + 1) as u32);
-        let string_ptr = self.malloc_array(&array_type);
-
-        for (index, c) in string.value.chars().enumerate() {
-            let char_ptr = unsafe {
-                self.builder.build_in_bounds_gep(
-                    string_ptr,
-                    &[i32_type.const_int(0, false), i32_type.const_int(index as u64, false)],
-                    format!("Gep char {} of string {}", index, string.value).as_ref())
-            };
-            self.builder.build_store(char_ptr, i8_type.const_int(c as u64, false));
-        }
-        // Store string terminating symbol
-        let last = unsafe {
-            self.builder.build_in_bounds_gep(
-                string_ptr,
-                &[i32_type.const_int(0, false), i32_type.const_int(string.value.len() as u64, false)],
-                format!("Gep the last position for the terminating symbol of string {}", string.value).as_ref())
-        };
-        self.builder.build_store(last, i8_type.const_int(0, false));
-
-        Value::String(
-            self.builder.build_pointer_cast(string_ptr, self.context.i8_type().ptr_type(AddressSpace::Generic), "Cast to eliminate the size info")
-        )
+        Value::String(self.get_string_constant(&string.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::{Deref, DerefMut};
+
+    use index::build;
+    use ::{parse, analyse};
+    use emit::apply;
+
+    #[test]
+    fn test_full() {
+        let content = r#"
+class Void
+end
+
+class Native__String
+end
+
+class String(underlying: Native__String)
+end
+
+def test(): Void
+  "打包\u{1F600}\n"
+  "打包\u{1F600}\n"
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
+
+        let module = apply(&[file.deref()]);
+        module.print_to_stderr();
+        assert_eq!(module.print_to_string().to_string().matches("private unnamed_addr constant").count(), 1);
+    }
+
+    #[test]
+    fn test_constants() {
+        let content = r#"
+class Void
+end
+
+class Native__String
+end
+
+class String(underlying: Native__String)
+end
+
+def test(): Void
+  "shared"
+  "a\0b"
+  "shared"
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
+
+        let module = apply(&[file.deref()]);
+        let ir = module.print_to_string().to_string();
+        assert!(module.verify().is_ok());
+        assert_eq!(ir.matches("c\"shared\\00\"").count(), 1);
+        assert!(ir.contains("private unnamed_addr constant [4 x i8] c\"a\\00b\\00\""));
     }
 }
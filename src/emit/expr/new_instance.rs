@@ -2,11 +2,12 @@ use parse::tree::{NewInstance, Class};
 use emit::{Value, Emitter};
 use emit::expr::ExprEmitter;
 use emit::helper::Helper;
-use inkwell::values::{PointerValue, BasicValueEnum};
+use inkwell::values::BasicValueEnum;
+use inkwell::AddressSpace;
 
 pub trait NewInstanceEmitter {
     fn apply_new_instance<'def>(&self, new_instance: &NewInstance<'def>) -> Value<'def>;
-    fn alloc_new_instance<'def>(&self, class: &Class<'def>, args: Vec<Value<'def>>) -> PointerValue;
+    fn alloc_new_instance<'def>(&self, class: &Class<'def>, args: Vec<Value<'def>>) -> BasicValueEnum;
 }
 
 impl NewInstanceEmitter for Emitter<'_> {
@@ -17,42 +18,168 @@ impl NewInstanceEmitter for Emitter<'_> {
         }
 
         let class = unsafe { &*new_instance.class_def.unwrap() };
-        Value::Class(self.alloc_new_instance(class, args), class)
+
+        if let Some(constructor) = new_instance.constructor_def {
+            let constructor = unsafe { &*constructor };
+            let mut llvm_args = vec![];
+            for (param, arg) in constructor.params.iter().zip(args.iter()) {
+                let param_class = unsafe { &*param.tpe.class_def.unwrap() };
+                llvm_args.push(self.wrap_with_class(arg, param_class));
+            }
+
+            let llvm_ret = self.builder.build_call(
+                constructor.llvm.get().unwrap(),
+                &llvm_args,
+                format!("Invoke a constructor of the class {}", class.name.fragment).as_ref());
+            return Value::Class(llvm_ret.try_as_basic_value().left().unwrap(), class);
+        }
+
+        let instance = self.alloc_new_instance(class, args);
+
+        if class.llvm_native.get().is_none() {
+            if let Some(initializer) = class.find_initializer() {
+                self.builder.build_call(
+                    initializer.llvm.get().unwrap(),
+                    &[instance],
+                    format!("Invoke the initializer of the class {}", class.name.fragment).as_ref());
+            }
+        }
+
+        Value::Class(instance, class)
     }
 
-    fn alloc_new_instance<'def>(&self, class: &Class<'def>, args: Vec<Value<'def>>) -> PointerValue {
-        let instance;
+    fn alloc_new_instance<'def>(&self, class: &Class<'def>, args: Vec<Value<'def>>) -> BasicValueEnum {
+        // An unboxed instance is its only value, e.g. `Int(underlying)` is the i64 of `underlying`.
+        if self.get_unboxed_type(class).is_some() {
+            let expected_value_class = match class.params.get(0) {
+                Some(param) => unsafe { &*param.tpe.class_def.unwrap() },
+                None => class,
+            };
+            return self.wrap_with_class(args.get(0).unwrap(), expected_value_class);
+        }
 
-        if let Some(llvm_native) = class.llvm_native.get() {
-            instance = self.malloc(&llvm_native);
-            let native_value_ptr = unsafe {
-                self.builder.build_struct_gep(instance, 0, format!("Gep for the native value of the class {}", class.name.fragment).as_ref())
+        // A native class without a value, e.g. Native__Null, is a null pointer.
+        if class.llvm_native.get().is_some() {
+            assert!(args.is_empty(), "Unable to instantiate the native class {} with args", class.name.fragment);
+            return BasicValueEnum::PointerValue(class.llvm.get().unwrap().ptr_type(AddressSpace::Generic).const_null());
+        }
+
+        let instance = self.malloc(&class.llvm.get().unwrap());
+        for (index, (param, arg)) in class.params.iter().zip(args.iter()).enumerate() {
+            let expected_value_class = unsafe { &*param.tpe.class_def.unwrap() };
+
+            let param_ptr = unsafe {
+                self.builder.build_struct_gep(instance, index as u32, format!("Gep for the field #{} of the class {}", index, class.name.fragment).as_ref())
             };
 
-            self.builder.build_store(
-                native_value_ptr,
-                match args.get(0).unwrap() {
-                    Value::Char(i) => BasicValueEnum::IntValue(*i),
-                    Value::Int(i) => BasicValueEnum::IntValue(*i),
-                    Value::String(i) => BasicValueEnum::PointerValue(*i),
-                    other => panic!(),
-                }
-            );
-        } else {
-            println!("{}", class.name.fragment);
-            instance = self.malloc(&class.llvm.get().unwrap());
-            for (index, (param, arg)) in class.params.iter().zip(args.iter()).enumerate() {
-                let expected_value_class = unsafe { &*param.tpe.class_def.unwrap() };
-
-                let param_ptr = unsafe {
-                    self.builder.build_struct_gep(instance, index as u32, format!("Gep for the field #{} of the class {}", index, class.name.fragment).as_ref())
+            self.builder.build_store(param_ptr, self.wrap_with_class(&arg, expected_value_class));
+        }
+
+        for field in &class.fields {
+            if let Some(value) = &field.value_opt {
+                let expected_value_class = unsafe { &*field.tpe.class_def.unwrap() };
+                let value = self.apply_expr(value);
+
+                let field_ptr = unsafe {
+                    self.builder.build_struct_gep(instance, field.index as u32, format!("Gep for the field #{} of the class {}", field.index, class.name.fragment).as_ref())
                 };
 
-                self.builder.build_store(param_ptr, self.wrap_with_class(&arg, expected_value_class));
+                self.builder.build_store(field_ptr, self.wrap_with_class(&value, expected_value_class));
             }
         }
 
+        if let Some(finalizer) = class.find_finalizer() {
+            self.gc_register_finalizer(instance, finalizer.llvm.get().unwrap());
+        }
+
+        BasicValueEnum::PointerValue(instance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::{Deref, DerefMut};
+
+    use index::build;
+    use ::{parse, analyse};
+    use emit::apply;
+
+    #[test]
+    fn test_full() {
+        let content = r#"
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+class Void
+end
+
+class Test(a: Int)
+  def init(): Void
+    a
+  end
+
+  def init(): Test
+    Test(1)
+  end
+end
+
+def main(): Int
+  Test()
+  Test(2)
+  3
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
+
+        let module = apply(&[file.deref()]);
+        module.print_to_stderr();
+    }
+
+    // Only an instance of a class with `def finalize(): Void` has a finalizer registered with the GC.
+    #[test]
+    fn test_finalizer() {
+        let content = r#"
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+class Void
+end
+
+class Plain(a: Int)
+end
+
+def main(): Int
+  Plain(1)
+  2
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
+
+        let module = apply(&[file.deref()]);
+        assert!(module.verify().is_ok());
+        assert!(module.get_function("GC_malloc").is_some());
+        assert!(module.get_function("lilit_gc_register_finalizer").is_none());
+
+        let content = content.replace("class Plain(a: Int)\n", "class Plain(a: Int)\n  def finalize(): Void\n  end\n");
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
 
-        instance
+        let module = apply(&[file.deref()]);
+        assert!(module.verify().is_ok());
+        assert!(module.get_function("lilit_gc_register_finalizer").is_some());
     }
 }
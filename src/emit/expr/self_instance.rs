@@ -0,0 +1,64 @@
+use emit::{Emitter, Value};
+use parse::tree::SelfInstance;
+use emit::helper::Helper;
+
+pub trait SelfInstanceEmitter {
+    fn apply_self_instance<'def>(&self, self_instance: &SelfInstance<'def>) -> Value<'def>;
+}
+
+impl SelfInstanceEmitter for Emitter<'_> {
+    fn apply_self_instance<'def>(&self, self_instance: &SelfInstance<'def>) -> Value<'def> {
+        let param = unsafe { &*self_instance.param_def.unwrap() };
+        let param_class = unsafe { &*param.tpe.class_def.unwrap() };
+
+        self.read_ptr(param.llvm.get().unwrap(), param_class)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::{Deref, DerefMut};
+
+    use index::build;
+    use ::{parse, analyse};
+    use emit::apply;
+
+    #[test]
+    fn test_full() {
+        let content = r#"
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+class Void
+end
+
+class Counter
+  var count: Int = 0
+
+  def set(count: Int): Void
+    self.count = count
+  end
+
+  def reset(): Void
+    set(0)
+  end
+end
+
+def main(): Int
+  counter = Counter()
+  counter.reset()
+  counter.count
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
+
+        let module = apply(&[file.deref()]);
+        module.print_to_stderr();
+    }
+}
@@ -0,0 +1,83 @@
+use emit::{Emitter, Value};
+use parse::tree::Using;
+use emit::expr::ExprEmitter;
+use emit::expr::assignment::AssignmentEmitter;
+use emit::helper::Helper;
+use inkwell::values::BasicValueEnum;
+
+pub trait UsingEmitter {
+    fn apply_using<'def>(&self, using: &Using<'def>) -> Value<'def>;
+}
+
+impl UsingEmitter for Emitter<'_> {
+    // The instance is finalized right after the last expr, and its GC finalizer is removed, so it isn't finalized twice.
+    fn apply_using<'def>(&self, using: &Using<'def>) -> Value<'def> {
+        let (instance, class) = unwrap2!(Value::Class, self.apply_assignment(&using.assignment));
+        let class = unsafe { &*class };
+
+        let mut ret = Value::Void;
+        for expr in &using.exprs {
+            ret = self.apply_expr(expr);
+        }
+
+        let finalizer = class.find_finalizer().unwrap();
+        self.builder.build_call(
+            finalizer.llvm.get().unwrap(),
+            &[instance],
+            format!("Finalize {} at the end of using", using.assignment.name.fragment).as_ref());
+        self.gc_unregister_finalizer(unwrap!(BasicValueEnum::PointerValue, instance));
+
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::{Deref, DerefMut};
+
+    use index::build;
+    use ::{parse, analyse};
+    use emit::apply;
+
+    #[test]
+    fn test_full() {
+        let content = r#"
+class Void
+end
+
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+def native__close(fd: Native__Int): Native__Int
+end
+
+class Pipe(fd: Int)
+  def finalize(): Void
+    native__close(fd.underlying)
+  end
+
+  def read(): Int
+    fd
+  end
+end
+
+def main: Int
+  using pipe = Pipe(3)
+    pipe.read()
+  end
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
+
+        let module = apply(&[file.deref()]);
+        module.print_to_stderr();
+        assert!(module.get_function("lilit_gc_register_finalizer").is_some());
+        assert!(module.get_function("lilit_gc_unregister_finalizer").is_some());
+    }
+}
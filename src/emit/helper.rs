@@ -1,23 +1,60 @@
-use inkwell::types::{StructType, FunctionType, ArrayType, BasicTypeEnum};
-use inkwell::values::{PointerValue, FunctionValue, BasicValueEnum};
+use inkwell::types::{StructType, FunctionType, ArrayType, BasicTypeEnum, IntType, PointerType};
+use inkwell::values::{PointerValue, FunctionValue, BasicValueEnum, IntValue};
 use emit::{Emitter, Value};
 use inkwell::AddressSpace;
 use inkwell::attributes::Attribute;
 use inkwell::module::Linkage;
-use parse::tree::Class;
+use parse::tree::{Class, Param};
+use tokenize::span::Span;
 use emit::expr::new_instance::NewInstanceEmitter;
 
 pub trait Helper {
     fn malloc_array(&self, array_type: &ArrayType) -> PointerValue;
     fn malloc(&self, struct_type: &StructType) -> PointerValue;
     fn get_external_func(&self, name: &str, tpe: FunctionType) -> FunctionValue;
-    fn wrap_with_class<'def>(&self, value: &Value<'def>, expected_class: &Class<'def>) -> PointerValue;
+    fn wrap_with_class<'def>(&self, value: &Value<'def>, expected_class: &Class<'def>) -> BasicValueEnum;
     fn to_value<'def>(&self, value: BasicValueEnum, class: &Class<'def>) -> Value<'def>;
     fn gc_init(&self);
     fn gc_collect(&self);
-    fn gc_register_finalizer(&self, ptr: PointerValue);
+    fn gc_register_finalizer(&self, ptr: PointerValue, finalizer: FunctionValue);
+    fn gc_unregister_finalizer(&self, ptr: PointerValue);
+    fn get_string_constant(&self, value: &str) -> PointerValue;
+    fn build_panic(&self, message: PointerValue, span: Span);
+    fn build_panic_if(&self, condition: IntValue, message: &str, span: Span);
     fn read_ptr<'def>(&self, alloca_ptr: PointerValue, class: &Class<'def>) -> Value<'def>;
     fn get_type_for_native(&self, class: &Class) -> BasicTypeEnum;
+    fn get_native_int_type(&self, class: &Class) -> Option<IntType>;
+    fn get_unboxed_type(&self, class: &Class) -> Option<BasicTypeEnum>;
+    fn get_underlying(&self, instance: BasicValueEnum, class: &Class) -> BasicValueEnum;
+    fn get_llvm_type(&self, class: &Class) -> BasicTypeEnum;
+    fn get_param_type(&self, param: &Param) -> BasicTypeEnum;
+}
+
+// Unsigned native ints are zero-extended when they're promoted, e.g. when passed as varargs.
+pub fn is_unsigned_native_int(class: &Class) -> bool {
+    class.name.fragment.starts_with("Native__UInt") || ["Native__Byte", "Native__Bool", "Native__USize"].contains(&class.name.fragment)
+}
+
+pub fn get_ptr_type(tpe: BasicTypeEnum) -> PointerType {
+    match tpe {
+        BasicTypeEnum::ArrayType(t) => t.ptr_type(AddressSpace::Generic),
+        BasicTypeEnum::FloatType(t) => t.ptr_type(AddressSpace::Generic),
+        BasicTypeEnum::IntType(t) => t.ptr_type(AddressSpace::Generic),
+        BasicTypeEnum::PointerType(t) => t.ptr_type(AddressSpace::Generic),
+        BasicTypeEnum::StructType(t) => t.ptr_type(AddressSpace::Generic),
+        BasicTypeEnum::VectorType(t) => t.ptr_type(AddressSpace::Generic),
+    }
+}
+
+pub fn get_fn_type(return_type: BasicTypeEnum, param_types: &[BasicTypeEnum], is_varargs: bool) -> FunctionType {
+    match return_type {
+        BasicTypeEnum::ArrayType(t) => t.fn_type(param_types, is_varargs),
+        BasicTypeEnum::FloatType(t) => t.fn_type(param_types, is_varargs),
+        BasicTypeEnum::IntType(t) => t.fn_type(param_types, is_varargs),
+        BasicTypeEnum::PointerType(t) => t.fn_type(param_types, is_varargs),
+        BasicTypeEnum::StructType(t) => t.fn_type(param_types, is_varargs),
+        BasicTypeEnum::VectorType(t) => t.fn_type(param_types, is_varargs),
+    }
 }
 
 impl Helper for Emitter<'_> {
@@ -32,7 +69,6 @@ impl Helper for Emitter<'_> {
             BasicValueEnum::PointerValue(p) => p,
             other => panic!("Expect BasicValueEnum::PointerValue, found {:?}", other),
         };
-        self.gc_register_finalizer(p);
 
         self.builder.build_pointer_cast(p, array_type.ptr_type(AddressSpace::Generic), "Cast pointer to ArrayType")
     }
@@ -49,7 +85,6 @@ impl Helper for Emitter<'_> {
             BasicValueEnum::PointerValue(p) => p,
             x => panic!("Expect BasicValueEnum::PointerValue, found {:?}", x),
         };
-        self.gc_register_finalizer(p);
 
         self.builder.build_pointer_cast(p, struct_type.ptr_type(AddressSpace::Generic), "cast")
     }
@@ -65,37 +100,24 @@ impl Helper for Emitter<'_> {
         }
     }
 
-    fn wrap_with_class<'def>(&self, value: &Value<'def>, expected_class: &Class<'def>) -> PointerValue {
+    // A native value is unboxed, so it's used as it is. A native struct becomes an instance of its class.
+    fn wrap_with_class<'def>(&self, value: &Value<'def>, expected_class: &Class<'def>) -> BasicValueEnum {
         match value {
             Value::Char(i) => {
                 assert_eq!("Native__Char", expected_class.name.fragment);
-                let instance = self.malloc(&expected_class.llvm.get().unwrap());
-
-                let param_ptr = unsafe {
-                    self.builder.build_struct_gep(instance, 0 as u32, format!("Gep for the native param of the class {}", expected_class.name.fragment).as_ref())
-                };
-                self.builder.build_store(param_ptr, BasicValueEnum::IntValue(*i));
-                instance
+                BasicValueEnum::IntValue(*i)
+            },
+            Value::Float(f) => {
+                assert_eq!("Native__Float", expected_class.name.fragment);
+                BasicValueEnum::FloatValue(*f)
             },
             Value::Int(i) => {
-                assert_eq!("Native__Int", expected_class.name.fragment);
-                let instance = self.malloc(&expected_class.llvm.get().unwrap());
-
-                let param_ptr = unsafe {
-                    self.builder.build_struct_gep(instance, 0 as u32, format!("Gep for the native param of the class {}", expected_class.name.fragment).as_ref())
-                };
-                self.builder.build_store(param_ptr, BasicValueEnum::IntValue(*i));
-                instance
+                assert!(self.get_native_int_type(expected_class).is_some(), "Expect a native int class, found {}", expected_class.name.fragment);
+                BasicValueEnum::IntValue(*i)
             },
             Value::String(i) => {
                 assert_eq!("Native__String", expected_class.name.fragment);
-                let instance = self.malloc(&expected_class.llvm.get().unwrap());
-
-                let param_ptr = unsafe {
-                    self.builder.build_struct_gep(instance, 0 as u32, format!("Gep for the native param of the class {}", expected_class.name.fragment).as_ref())
-                };
-                self.builder.build_store(param_ptr, BasicValueEnum::PointerValue(*i));
-                instance
+                BasicValueEnum::PointerValue(*i)
             },
             Value::Struct(struct_ptr, class) => {
                 let struct_ptr = *struct_ptr;
@@ -115,12 +137,12 @@ impl Helper for Emitter<'_> {
                     let param_class = unsafe { &*param.tpe.class_def.unwrap() };
                     self.builder.build_store(param_ptr, self.wrap_with_class(&self.to_value(struct_field_value, param_class), param_class));
                 }
-                instance
+                BasicValueEnum::PointerValue(instance)
             },
-            Value::Class(ptr, class) => {
+            Value::Class(value, class) => {
                 let class = unsafe { &**class };
                 assert_eq!(expected_class.name.fragment, class.name.fragment);
-                *ptr
+                *value
             },
             Value::Void => panic!(),
         }
@@ -128,8 +150,9 @@ impl Helper for Emitter<'_> {
 
     fn to_value<'def>(&self, value: BasicValueEnum, class: &Class<'def>) -> Value<'def> {
         match class.name.fragment {
-            "Native__Int" => Value::Int(unwrap!(BasicValueEnum::IntValue, value)),
+            _ if self.get_native_int_type(class).is_some() => Value::Int(unwrap!(BasicValueEnum::IntValue, value)),
             "Native__Char" => Value::Char(unwrap!(BasicValueEnum::IntValue, value)),
+            "Native__Float" => Value::Float(unwrap!(BasicValueEnum::FloatValue, value)),
             "Native__String" => Value::String(unwrap!(BasicValueEnum::PointerValue, value)),
             "Native__Void" => Value::Void,
             other if other.starts_with("Native__Struct__") => Value::Struct(unwrap!(BasicValueEnum::PointerValue, value), class),
@@ -141,7 +164,7 @@ impl Helper for Emitter<'_> {
         let fn_type = self.context
             .void_type()
             .fn_type(&[], false);
-        let func = self.get_external_func("GC_init", fn_type);
+        let func = self.get_external_func("lilit_gc_init", fn_type);
 
         self.builder.build_call(func, &[], "gc_init");
     }
@@ -155,58 +178,186 @@ impl Helper for Emitter<'_> {
         self.builder.build_call(func, &[], "gc_gcollect");
     }
 
-    fn gc_register_finalizer(&self, ptr: PointerValue) {
-        let finalizer_func = self.get_external_func(
-            "GC_finalizer",
-            self.context.void_type().fn_type(
-                &[
-                    self.context.i8_type().ptr_type(AddressSpace::Generic).into(),
-                    self.context.i8_type().ptr_type(AddressSpace::Generic).into()
-                ],
-                false
-            ),
+    fn gc_register_finalizer(&self, ptr: PointerValue, finalizer: FunctionValue) {
+        let i8_ptr_type = self.context.i8_type().ptr_type(AddressSpace::Generic);
+        let func = self.get_external_func(
+            "lilit_gc_register_finalizer",
+            self.context.void_type().fn_type(&[i8_ptr_type.into(), i8_ptr_type.into()], false)
         );
 
-        let param_types = vec![
-            self.context.i8_type().ptr_type(AddressSpace::Generic).into(),
-            finalizer_func.as_global_value().as_pointer_value().get_type().into(),
-            self.context.i8_type().ptr_type(AddressSpace::Generic).into(),
-            finalizer_func.get_type().ptr_type(AddressSpace::Generic).into(),
-            self.context.i8_type().ptr_type(AddressSpace::Generic).ptr_type(AddressSpace::Generic).into(),
-        ];
-        let func_type = self.context
-            .void_type()
-            .fn_type(&param_types, false);
-        let func = self.get_external_func("GC_register_finalizer", func_type);
-
         self.builder.build_call(
             func,
             &[
-                ptr.into(),
-                finalizer_func.as_global_value().as_pointer_value().into(),
-                self.context.i8_type().ptr_type(AddressSpace::Generic).const_null().into(),
-                finalizer_func.get_type().ptr_type(AddressSpace::Generic).const_null().into(),
-                self.context.i8_type().ptr_type(AddressSpace::Generic).ptr_type(AddressSpace::Generic).const_null().into(),
+                self.builder.build_pointer_cast(ptr, i8_ptr_type, "Cast the instance to register its finalizer").into(),
+                self.builder.build_pointer_cast(finalizer.as_global_value().as_pointer_value(), i8_ptr_type, "Cast the finalizer").into(),
             ],
             "register_finalizer"
         );
     }
 
+    fn gc_unregister_finalizer(&self, ptr: PointerValue) {
+        let i8_ptr_type = self.context.i8_type().ptr_type(AddressSpace::Generic);
+        let func = self.get_external_func(
+            "lilit_gc_unregister_finalizer",
+            self.context.void_type().fn_type(&[i8_ptr_type.into()], false)
+        );
+
+        self.builder.build_call(
+            func,
+            &[self.builder.build_pointer_cast(ptr, i8_ptr_type, "Cast the instance to unregister its finalizer").into()],
+            "unregister_finalizer"
+        );
+    }
+
+    // Every use of the same text shares one private constant. The constant is built from the bytes, so a literal may
+    // contain a NUL, e.g. "a\0b", which C reads as the end of the string.
+    fn get_string_constant(&self, value: &str) -> PointerValue {
+        let mut string_constants = self.string_constants.borrow_mut();
+        *string_constants
+            .entry(value.to_string())
+            .or_insert_with(|| {
+                let i8_type = self.context.i8_type();
+                let bytes = value.bytes().chain(Some(0)).map(|b| i8_type.const_int(u64::from(b), false)).collect::<Vec<_>>();
+
+                let global = self.module.add_global(i8_type.array_type(bytes.len() as u32), None, "lilit_string");
+                global.set_initializer(&i8_type.const_array(&bytes));
+                global.set_constant(true);
+                global.set_linkage(Linkage::Private);
+                global.set_unnamed_addr(true);
+
+                let zero = self.context.i64_type().const_int(0, false);
+                unsafe { global.as_pointer_value().const_gep(&[zero, zero]) }
+            })
+    }
+
+    // `lilit_panic` prints the message, where it happened and the backtrace, and exits, so the rest of the block is
+    // unreachable. The builder moves to a new block, which has no predecessor, for whatever is emitted after it.
+    fn build_panic(&self, message: PointerValue, span: Span) {
+        let i8_ptr_type = self.context.i8_type().ptr_type(AddressSpace::Generic);
+        let i64_type = self.context.i64_type();
+        let func = self.get_external_func(
+            "lilit_panic",
+            self.context.void_type().fn_type(&[i8_ptr_type.into(), i8_ptr_type.into(), i64_type.into(), i64_type.into()], false)
+        );
+        // noreturn is an attribute of the function itself, which is at the last index, not of its return value.
+        func.add_attribute(u32::max_value(), self.context.create_enum_attribute(Attribute::get_named_enum_kind_id("noreturn"), 0));
+
+        let path = &unsafe { &*span.file }.path;
+        self.builder.build_call(
+            func,
+            &[
+                message.into(),
+                self.get_string_constant(path).into(),
+                i64_type.const_int(span.line as u64, false).into(),
+                i64_type.const_int(span.col as u64, false).into(),
+            ],
+            "panic"
+        );
+        self.builder.build_unreachable();
+
+        let function = self.builder.get_insert_block().unwrap().get_parent().unwrap();
+        let after_block = self.context.append_basic_block(&function, "after_panic");
+        self.builder.position_at_end(&after_block);
+    }
+
+    // The checks inserted by the compiler, e.g. against a division by zero, panic at the span of the invoke.
+    fn build_panic_if(&self, condition: IntValue, message: &str, span: Span) {
+        let function = self.builder.get_insert_block().unwrap().get_parent().unwrap();
+        let panic_block = self.context.append_basic_block(&function, "check_failed");
+        let ok_block = self.context.append_basic_block(&function, "check_ok");
+        self.builder.build_conditional_branch(condition, &panic_block, &ok_block);
+
+        self.builder.position_at_end(&panic_block);
+        self.build_panic(self.get_string_constant(message), span);
+        self.builder.build_unreachable(); // Terminates the empty block after the panic.
+
+        self.builder.position_at_end(&ok_block);
+    }
+
     fn read_ptr<'def>(&self, alloca_ptr: PointerValue, class: &Class<'def>) -> Value<'def> {
         let value = self.builder.build_load(
             alloca_ptr,
             format!("Read ptr into {}", class.name.fragment).as_ref()
         );
 
-        Value::Class(unwrap!(BasicValueEnum::PointerValue, value), class)
+        Value::Class(value, class)
     }
 
     fn get_type_for_native(&self, class: &Class) -> BasicTypeEnum {
+        if let Some(int_type) = self.get_native_int_type(class) {
+            return int_type.into();
+        }
+
         match class.name.fragment {
-            "Native__Int" => self.context.i64_type().into(),
             "Native__String" => self.context.i8_type().ptr_type(AddressSpace::Generic).into(),
             "Native__Char" => self.context.i8_type().into(),
+            "Native__Float" => self.context.f64_type().into(),
             other => panic!("Unrecognized {}", other),
         }
     }
-}
\ No newline at end of file
+
+    fn get_native_int_type(&self, class: &Class) -> Option<IntType> {
+        match class.name.fragment {
+            "Native__Int" | "Native__Int64" | "Native__UInt64" => Some(self.context.i64_type()),
+            "Native__Int32" | "Native__UInt32" => Some(self.context.i32_type()),
+            "Native__Int16" | "Native__UInt16" => Some(self.context.i16_type()),
+            "Native__Int8" | "Native__UInt8" | "Native__Byte" => Some(self.context.i8_type()),
+            "Native__Bool" => Some(self.context.bool_type()),
+            "Native__Size" | "Native__USize" => Some(self.ptr_sized_int_type),
+            _ => None,
+        }
+    }
+
+    // Int, Char, Boolean and Float, which only wrap their native value, e.g. `Int(underlying: Native__Int)`, are
+    // represented by that value instead of a pointer to a struct, so creating them doesn't allocate. Any other class is
+    // boxed, including one that wraps a single value, e.g. `Meter(value: Int)`, and so is one of the four that declares
+    // a field or a finalizer, because a field can be assigned and the GC only finalizes what it allocates.
+    fn get_unboxed_type(&self, class: &Class) -> Option<BasicTypeEnum> {
+        match class.name.fragment {
+            "Native__Char" | "Native__Float" | "Native__String" => return Some(self.get_type_for_native(class)),
+            other if other.starts_with("Native__") => return self.get_native_int_type(class).map(BasicTypeEnum::IntType),
+            "Int" | "Char" | "Boolean" | "Float" => (),
+            _ => return None,
+        }
+
+        if class.params.len() != 1 || class.params[0].is_varargs || !class.fields.is_empty() || class.find_finalizer().is_some() {
+            return None;
+        }
+
+        let param_class = unsafe { &*class.params[0].tpe.class_def.unwrap() };
+        if param_class.name.fragment.starts_with("Native__") {
+            self.get_unboxed_type(param_class)
+        } else {
+            None
+        }
+    }
+
+    // The value of the first param of an instance, e.g. the native string of a String, which is the instance itself when
+    // it's unboxed.
+    fn get_underlying(&self, instance: BasicValueEnum, class: &Class) -> BasicValueEnum {
+        if self.get_unboxed_type(class).is_some() {
+            return instance;
+        }
+
+        let param_ptr = unsafe {
+            self.builder.build_struct_gep(unwrap!(BasicValueEnum::PointerValue, instance), 0, format!("Gep the underlying value of {}", class.name.fragment).as_ref())
+        };
+        self.builder.build_load(param_ptr, &format!("Load the underlying value of {}", class.name.fragment))
+    }
+
+    fn get_llvm_type(&self, class: &Class) -> BasicTypeEnum {
+        match self.get_unboxed_type(class) {
+            Some(tpe) => tpe,
+            None => BasicTypeEnum::PointerType(class.llvm.get().unwrap().ptr_type(AddressSpace::Generic)),
+        }
+    }
+
+    fn get_param_type(&self, param: &Param) -> BasicTypeEnum {
+        let param_class = unsafe { &*param.tpe.class_def.unwrap() };
+        if param.is_varargs {
+            BasicTypeEnum::PointerType(get_ptr_type(self.get_llvm_type(param_class)))
+        } else {
+            self.get_llvm_type(param_class)
+        }
+    }
+}
@@ -2,78 +2,159 @@ use LilitFile;
 use inkwell::module::Module;
 use inkwell::context::Context;
 use inkwell::builder::Builder;
-use parse::tree::{CompilationUnitItem, Class};
-use inkwell::values::{IntValue, PointerValue, ArrayValue, BasicValueEnum};
+use parse::tree::{CompilationUnitItem, Class, Method};
+use inkwell::values::{IntValue, FloatValue, PointerValue, ArrayValue, BasicValueEnum};
 use emit::def::method::EmitterMethod;
 use emit::def::class::ClassEmitter;
-use inkwell::types::{StructType, BasicTypeEnum};
+use emit::debug_info::DebugInfo;
+use inkwell::types::{StructType, BasicTypeEnum, IntType};
 use inkwell::AddressSpace;
+use inkwell::targets::{TargetMachine, TargetData};
+use std::cell::RefCell;
+use std::collections::HashMap;
 
+pub mod debug_info;
 pub mod def;
 pub mod expr;
 pub mod helper;
+pub mod optimize;
 
 struct Emitter<'r> {
     context: Context,
     builder: Builder,
     module: &'r Module,
-    va_list_struct_type: StructType,
+    va_list_struct_type: Option<StructType>, // None when varargs aren't supported on the target.
+    ptr_sized_int_type: IntType, // Native__Size and Native__USize, which are as wide as a pointer on the target.
+    string_constants: RefCell<HashMap<String, PointerValue>>, // The global constant of each string literal.
+    debug_info: Option<DebugInfo>, // None without `-g`.
 }
 
 #[derive(PartialEq, Debug, Copy, Clone)]
 pub enum Value<'def> {
     Void,
     Char(IntValue),
+    Float(FloatValue),
     Int(IntValue),
     String(PointerValue),
     Struct(PointerValue, *const Class<'def>),
-    Class(PointerValue, *const Class<'def>),
+    // An instance is a pointer to its struct unless its class is unboxed, e.g. Int is an i64.
+    Class(BasicValueEnum, *const Class<'def>),
 }
 
+// LLVM's default data layout has 64-bit pointers, like the hosts that Lilit supports.
 pub fn apply(files: &[&LilitFile]) -> Module {
+    apply_for_target(files, &TargetMachine::get_default_triple().to_string(), &TargetData::create(""), false)
+}
+
+// The optimizations and the size of Native__Size depend on the data layout, so it's set before anything is emitted.
+// With debug info, the first file is the compile unit.
+pub fn apply_for_target(files: &[&LilitFile], triple: &str, target_data: &TargetData, is_debug: bool) -> Module {
     let context = Context::create();
     let module = context.create_module("main");
+    module.set_triple(triple);
+    module.set_data_layout(&target_data.get_data_layout());
     let builder = context.create_builder();
-    let va_list_struct_type = context.struct_type(
-        &vec![
-            BasicTypeEnum::IntType(context.i32_type()),
-            BasicTypeEnum::IntType(context.i32_type()),
-            BasicTypeEnum::PointerType(context.i8_type().ptr_type(AddressSpace::Generic)),
-            BasicTypeEnum::PointerType(context.i8_type().ptr_type(AddressSpace::Generic)),
-        ],
-        false,
-    );
+    let va_list_struct_type = get_va_list_type(&context, triple);
+    let ptr_sized_int_type = context.ptr_sized_int_type(target_data, None);
     let emitter = Emitter {
         context,
         builder,
         module: &module,
         va_list_struct_type,
+        ptr_sized_int_type,
+        string_constants: RefCell::new(HashMap::new()),
+        debug_info: match files.first() {
+            Some(file) if is_debug => Some(DebugInfo::new(&file.path, ptr_sized_int_type.get_bit_width())),
+            _ => None,
+        },
     };
 
     emitter.apply(files);
 
+    if let Some(debug_info) = emitter.debug_info {
+        debug_info.finalize();
+    }
+
     module
 }
 
+// A native method with varargs needs the va_list of the target, so it's an error on a target without one. It's checked
+// before anything is emitted, like an error in analyse.
+pub fn check_target(files: &[&LilitFile], triple: &str) -> Result<(), String> {
+    if get_va_list_type(&Context::create(), triple).is_some() {
+        return Ok(());
+    }
+
+    for file in files {
+        for item in &file.unit.items {
+            let methods: Vec<&Method> = match item {
+                CompilationUnitItem::Class(class) => class.methods.iter().collect(),
+                CompilationUnitItem::Method(method) => vec![method],
+            };
+
+            for method in methods {
+                if method.name.fragment.starts_with("native__") && method.params.last().map_or(false, |p| p.is_varargs) {
+                    return Err(format!(
+                        "Varargs of native methods aren't supported on {}, but {} takes them at line {}, column {}",
+                        triple,
+                        method.name.fragment,
+                        method.name.line,
+                        method.name.col
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// The layout of va_list differs between the architectures. On x86_64, it's `{ i32, i32, i8*, i8* }`, and, on AArch64
+// except Apple's, it's `{ i8*, i8*, i8*, i32, i32 }`. Both are passed to a native function as a pointer. On Windows and
+// Apple's AArch64, va_list is a plain `i8*`, which isn't supported yet.
+fn get_va_list_type(context: &Context, triple: &str) -> Option<StructType> {
+    let int = BasicTypeEnum::IntType(context.i32_type());
+    let ptr = BasicTypeEnum::PointerType(context.i8_type().ptr_type(AddressSpace::Generic));
+
+    if triple.contains("windows") {
+        None
+    } else if triple.starts_with("x86_64") {
+        Some(context.struct_type(&[int, int, ptr, ptr], false))
+    } else if triple.starts_with("aarch64") && !triple.contains("apple") {
+        Some(context.struct_type(&[ptr, ptr, ptr, int, int], false))
+    } else {
+        None
+    }
+}
+
 impl <'r> Emitter<'r> {
     fn apply<'def>(&self, files: &[&LilitFile<'def>]) {
+        // Every class and method is declared before any method body is emitted
+        // because a body may refer to a class or a method declared later, e.g. a constructor.
         for file in files {
-            self.apply_file(file);
+            for item in &file.unit.items {
+                match item {
+                    CompilationUnitItem::Class(class) => self.apply_class_def(class),
+                    CompilationUnitItem::Method(_) => (),
+                }
+            }
         }
-    }
 
-    fn apply_file<'def>(&self, file: &LilitFile<'def> ) {
-        for item in &file.unit.items {
-            match item {
-                CompilationUnitItem::Class(class) => self.apply_class_def(class),
-                CompilationUnitItem::Method(method) => (),
+        for file in files {
+            for item in &file.unit.items {
+                match item {
+                    CompilationUnitItem::Class(class) => self.apply_class(class),
+                    CompilationUnitItem::Method(method) => self.apply_method_def(method),
+                }
             }
         }
 
-        for item in &file.unit.items {
-            match item {
-                CompilationUnitItem::Class(class) => self.apply_class(class),
-                CompilationUnitItem::Method(method) => self.apply_method(method),
+        for file in files {
+            for item in &file.unit.items {
+                match item {
+                    CompilationUnitItem::Class(class) => self.apply_class_methods(class),
+                    CompilationUnitItem::Method(method) => self.apply_method(method),
+                }
             }
         }
     }
@@ -88,7 +169,9 @@ mod tests {
     use parse::tree::{CompilationUnit, Type, CompilationUnitItem, Method, Invoke, Expr, Int, NewInstance, NativeInt};
     use test_common::span2;
     use std::cell::{Cell, RefCell};
-    use emit::apply;
+    use emit::{apply, apply_for_target, check_target, get_va_list_type};
+    use inkwell::context::Context;
+    use inkwell::targets::{TargetData, TargetMachine};
 
     #[test]
     fn test_full() {
@@ -131,4 +214,256 @@ end
         let module = apply(&[file.deref()]);
         module.print_to_stderr();
     }
+
+    #[test]
+    fn test_aarch64() {
+        let content = r#"
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+class Native__String
+end
+
+class Native__Any
+end
+
+def native__vprintf(text: Native__String, args...: Native__Any): Native__Int
+end
+
+def main: Int
+  native__vprintf("%d".underlying, 1.underlying)
+  0
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
+
+        let target_data = TargetData::create("e-m:e-i8:8:32-i16:16:32-i64:64-i128:128-n32:64-S128");
+        let module = apply_for_target(&[file.deref()], "aarch64-unknown-linux-gnu", &target_data, false);
+        module.print_to_stderr();
+        assert!(module.verify().is_ok());
+        assert!(module.print_to_string().to_string().contains("alloca { i8*, i8*, i8*, i32, i32 }"));
+    }
+
+    #[test]
+    #[should_panic(expected = "main must return a class that unboxes to a native int, e.g. Int, but Meter doesn't at line 10, column 5")]
+    fn test_main_without_int() {
+        let content = r#"
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+class Meter(value: Int, unit: Int)
+end
+
+def main: Meter
+  Meter(1, 2)
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
+
+        apply(&[file.deref()]);
+    }
+
+    #[test]
+    fn test_debug_info() {
+        let content = r#"
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+def main: Int
+  a = 1
+  a
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), "main.lilit"));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
+
+        let module = apply_for_target(&[file.deref()], &TargetMachine::get_default_triple().to_string(), &TargetData::create(""), true);
+        module.print_to_stderr();
+        assert!(module.verify().is_ok());
+
+        let ir = module.print_to_string().to_string();
+        assert!(ir.contains("!DISubprogram(name: \"main\", linkageName: \"native__main\""));
+        assert!(ir.contains("!DILocalVariable(name: \"a\""));
+        assert!(ir.contains("!DIBasicType(name: \"Int\", size: 64, encoding: DW_ATE_signed)"));
+        assert!(ir.contains("!DILocation(line: 8, column: 7"));
+    }
+
+    #[test]
+    fn test_va_list_type() {
+        let context = Context::create();
+
+        assert!(get_va_list_type(&context, "x86_64-unknown-linux-gnu").is_some());
+        assert!(get_va_list_type(&context, "x86_64-pc-windows-msvc").is_none());
+        assert!(get_va_list_type(&context, "aarch64-pc-windows-msvc").is_none());
+        assert!(get_va_list_type(&context, "aarch64-apple-darwin").is_none());
+    }
+
+    #[test]
+    fn test_check_target() {
+        let content = r#"
+class Native__String
+end
+
+class Native__Any
+end
+
+class Native__Int
+end
+
+def native__vprintf(text: Native__String, args...: Native__Any): Native__Int
+end
+        "#;
+        let file = unwrap!(Ok, parse::apply(content.trim(), ""));
+
+        assert_eq!(check_target(&[file.deref()], "x86_64-unknown-linux-gnu"), Ok(()));
+        assert_eq!(
+            check_target(&[file.deref()], "aarch64-apple-darwin"),
+            Err("Varargs of native methods aren't supported on aarch64-apple-darwin, but native__vprintf takes them at line 10, column 5".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unboxed() {
+        let content = r##"
+class Native__Bool
+end
+
+class Boolean(underlying: Native__Bool)
+end
+
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+def native__lilit_int_add(a: Native__Int, b: Native__Int): Native__Int
+end
+
+def native__lilit_int_eq(a: Native__Int, b: Native__Int): Native__Bool
+end
+
+def add(a: Int, b: Int): Int
+  Int(native__lilit_int_add(a.underlying, b.underlying))
+end
+
+def main: Int
+  a = add(1, 2)
+  b = Boolean(native__lilit_int_eq(a.underlying, 3.underlying)) && true
+  a
+end
+        "##;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
+
+        let module = apply(&[file.deref()]);
+        module.print_to_stderr();
+        assert!(module.verify().is_ok());
+        assert!(module.get_function("GC_malloc").is_none());
+        assert!(module.print_to_string().to_string().contains("i64 @lilit_user_space__add(i64"));
+    }
+
+    // A class that wraps a single value isn't unboxed unless it's one of the primitives, so it's a pointer wherever it's
+    // created, passed, returned, assigned, read or interpolated.
+    #[test]
+    fn test_boxed() {
+        let content = r##"
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+class Native__String
+end
+
+class String(underlying: Native__String)
+end
+
+class Meter(value: Int)
+  def add(other: Meter): Meter
+    Meter(native__lilit_int_add(value.underlying, other.value.underlying))
+  end
+
+  def to_s(): String
+    "m"
+  end
+end
+
+def native__lilit_int_add(a: Native__Int, b: Native__Int): Native__Int
+end
+
+def twice(meter: Meter): Meter
+  meter.add(meter)
+end
+
+def main: Int
+  a = twice(Meter(1).add(Meter(2)))
+  s = "#{a} and #{"b"}"
+  a.value
+end
+        "##;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
+
+        let module = apply(&[file.deref()]);
+        let ir = module.print_to_string().to_string();
+        module.print_to_stderr();
+        assert!(module.verify().is_ok());
+        assert!(module.get_function("GC_malloc").is_some());
+        assert!(ir.contains("%Meter* @lilit_user_space__twice(%Meter*"));
+        assert!(ir.contains("%String* @lilit_user_space__Meter__to_s(%Meter*"));
+    }
+
+    #[test]
+    fn test_ptr_sized_int() {
+        let content = r#"
+class Native__Int
+end
+
+class Native__USize
+end
+
+class Int(underlying: Native__Int)
+end
+
+def native__lilit_int_to_usize(value: Native__Int): Native__USize
+end
+
+def main: Int
+  native__lilit_int_to_usize(1.underlying)
+  0
+end
+        "#;
+        let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+        let root = build(&[file.deref()]);
+
+        analyse::apply(&mut [file.deref_mut()], &root);
+
+        let target_data = TargetData::create("e-m:e-p:32:32-i64:64-n8:16:32-S128");
+        let module = apply_for_target(&[file.deref()], "i686-unknown-linux-gnu", &target_data, false);
+        assert!(module.verify().is_ok());
+        assert!(module.print_to_string().to_string().contains("i32 @native__lilit_int_to_usize(i64"));
+    }
 }
@@ -0,0 +1,148 @@
+use inkwell::module::Module;
+use inkwell::passes::{PassManager, PassManagerBuilder};
+use inkwell::OptimizationLevel;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum OptLevel {
+    O0,
+    O1,
+    O2,
+    O3,
+    Os,
+}
+
+impl OptLevel {
+    pub fn parse(flag: &str) -> Option<OptLevel> {
+        match flag {
+            "-O0" => Some(OptLevel::O0),
+            "-O1" => Some(OptLevel::O1),
+            "-O2" => Some(OptLevel::O2),
+            "-O3" => Some(OptLevel::O3),
+            "-Os" => Some(OptLevel::Os),
+            _ => None,
+        }
+    }
+
+    pub fn get_llvm_level(&self) -> OptimizationLevel {
+        match self {
+            OptLevel::O0 => OptimizationLevel::None,
+            OptLevel::O1 => OptimizationLevel::Less,
+            OptLevel::O2 | OptLevel::Os => OptimizationLevel::Default,
+            OptLevel::O3 => OptimizationLevel::Aggressive,
+        }
+    }
+
+    // The same thresholds as clang. -O1 only inlines the functions that must always be inlined.
+    fn get_inline_threshold(&self) -> Option<u32> {
+        match self {
+            OptLevel::O0 | OptLevel::O1 => None,
+            OptLevel::O2 => Some(225),
+            OptLevel::O3 => Some(275),
+            OptLevel::Os => Some(75),
+        }
+    }
+}
+
+// Runs the function passes, e.g. mem2reg and GVN, on every function, and then the module passes, e.g. inlining.
+// -O0 runs nothing, so the emitted code stays as it is.
+pub fn apply(module: &Module, level: OptLevel) {
+    if level == OptLevel::O0 {
+        return;
+    }
+
+    let builder = PassManagerBuilder::create();
+    builder.set_optimization_level(level.get_llvm_level());
+    builder.set_size_level(if level == OptLevel::Os { 1 } else { 0 });
+
+    if let Some(threshold) = level.get_inline_threshold() {
+        builder.set_inliner_with_threshold(threshold);
+    }
+
+    // Every local and param is an alloca, so mem2reg runs first regardless of what the builder adds.
+    let function_passes = PassManager::create_for_function(module);
+    function_passes.add_promote_memory_to_register_pass();
+    builder.populate_function_pass_manager(&function_passes);
+
+    function_passes.initialize();
+    let mut function_opt = module.get_first_function();
+    while let Some(function) = function_opt {
+        function_passes.run_on_function(&function);
+        function_opt = function.get_next_function();
+    }
+    function_passes.finalize();
+
+    let module_passes = PassManager::create_for_module();
+    if level == OptLevel::O1 {
+        module_passes.add_always_inliner_pass();
+    }
+    builder.populate_module_pass_manager(&module_passes);
+    module_passes.run_on_module(module);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::{Deref, DerefMut};
+    use std::ptr::null;
+
+    use ::{analyse, emit, index, parse};
+    use emit::optimize::{apply, OptLevel};
+    use inkwell::module::Module;
+    use inkwell::targets::{InitializationConfig, Target};
+    use inkwell::OptimizationLevel;
+
+    extern "C" fn lilit_gc_init() {}
+
+    extern "C" fn lilit_int_add(a: i64, b: i64) -> i64 {
+        a + b
+    }
+
+    // Runs main in the JIT, where the runtime is replaced by the functions above.
+    fn run(module: &Module) -> i32 {
+        let engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+        engine.add_global_mapping(&module.get_function("lilit_gc_init").unwrap(), lilit_gc_init as usize);
+        engine.add_global_mapping(&module.get_function("lilit_int_add").unwrap(), lilit_int_add as usize);
+
+        unsafe {
+            let main = engine.get_function::<unsafe extern "C" fn(i32, *const *const i8) -> i32>("main").unwrap();
+            main.call(0, null())
+        }
+    }
+
+    #[test]
+    fn test_levels() {
+        let content = r#"
+class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+def native__lilit_int_add(a: Native__Int, b: Native__Int): Native__Int
+end
+
+def add(a: Int, b: Int): Int
+  Int(native__lilit_int_add(a.underlying, b.underlying))
+end
+
+def main: Int
+  a = add(1, 2)
+  add(a, 3)
+end
+        "#;
+
+        Target::initialize_native(&InitializationConfig::default()).unwrap();
+
+        for level in &[OptLevel::O0, OptLevel::O1, OptLevel::O2, OptLevel::O3, OptLevel::Os] {
+            let mut file = unwrap!(Ok, parse::apply(content.trim(), ""));
+            let root = index::build(&[file.deref()]);
+            analyse::apply(&mut [file.deref_mut()], &root);
+
+            let module = emit::apply(&[file.deref()]);
+            apply(&module, *level);
+
+            assert!(module.verify().is_ok());
+            module.print_to_stderr();
+            assert_eq!(run(&module), 6, "main returns a different value at {:?}", level);
+        }
+    }
+}
@@ -0,0 +1,428 @@
+use std::cmp::max;
+
+use LilitFile;
+use parse::tree::{Class, CompilationUnitItem, Expr, Method, Param};
+use tokenize;
+use tokenize::span::Span;
+use tokenize::token::Token;
+
+const INDENT: &str = "  ";
+
+// Prints the parse tree back as the canonical code: 2-space indentation, `end` aligned with its `class` or `def`,
+// `name: Type` params separated by `, `, and a blank line between the top-level items and between the methods.
+// The parse tree has no comments, so they come from the tokens. A comment is written on its own line before the
+// code that follows it, or at the end of the line when it follows code on the same line.
+pub fn apply(file: &LilitFile) -> String {
+    let tokens = tokenize::apply_with_comments(&file.content, file).expect("The file must be parsed before it is formatted");
+    let mut comments = vec![];
+    let mut ends = vec![];
+
+    for token in tokens {
+        match token {
+            Token::Comment(span) | Token::DocComment(span) => comments.push(span),
+            Token::Keyword(span) if span.fragment == "end" => ends.push(span),
+            _ => (),
+        }
+    }
+
+    let mut formatter = Formatter {
+        output: String::new(),
+        comments,
+        next_comment: 0,
+        ends,
+        next_end: 0,
+        last_line: 0,
+        allow_blank_line: false,
+    };
+
+    for (index, item) in file.unit.items.iter().enumerate() {
+        if index > 0 {
+            formatter.write_blank_line();
+        }
+
+        match item {
+            CompilationUnitItem::Class(class) => formatter.write_class(class),
+            CompilationUnitItem::Method(method) => formatter.write_method(method, 0),
+        }
+    }
+
+    formatter.write_comments(None, 0);
+    formatter.output
+}
+
+struct Formatter<'a> {
+    output: String,
+    comments: Vec<Span<'a>>,
+    next_comment: usize,
+    // The `end` keywords appear in the same order as the classes and methods finish, so they're taken one by one.
+    ends: Vec<Span<'a>>,
+    next_end: usize,
+    // The last line of the code that has been written, which decides the blank lines and the trailing comments.
+    last_line: usize,
+    // A blank line in the code is kept, except right after a `class` or `def` line.
+    allow_blank_line: bool,
+}
+
+impl <'a> Formatter<'a> {
+    fn write_class(&mut self, class: &Class) {
+        self.begin_line(Some(class.name), 0);
+        let name = self.format_span(class.name);
+        let params = self.format_params(&class.params);
+        self.output.push_str(&format!("class {}{}", name, params));
+        self.end_line();
+        self.allow_blank_line = false;
+
+        let mut members = class.fields.iter().map(Member::Field)
+            .chain(class.methods.iter().map(Member::Method))
+            .collect::<Vec<_>>();
+        members.sort_by_key(|m| match m {
+            Member::Field(f) => (f.name.unwrap().line, f.name.unwrap().col),
+            Member::Method(m) => (m.name.line, m.name.col),
+        });
+
+        for (index, member) in members.iter().enumerate() {
+            match member {
+                Member::Field(field) => {
+                    self.begin_line(field.name, 1);
+                    let field = self.format_field(field);
+                    self.output.push_str(&field);
+                    self.end_line();
+                },
+                Member::Method(method) => {
+                    if index > 0 {
+                        self.write_blank_line();
+                    }
+                    self.write_method(method, 1);
+                },
+            }
+        }
+
+        self.write_end(0);
+    }
+
+    fn write_method(&mut self, method: &Method, indent: usize) {
+        self.begin_line(Some(method.name), indent);
+        let name = self.format_span(method.name);
+        let params = self.format_params(&method.params);
+        let return_type = self.format_span(method.return_type.span.unwrap());
+        self.output.push_str(&format!(
+            "{}def {}{}: {}",
+            if method.is_static { "static " } else { "" },
+            name,
+            params,
+            return_type
+        ));
+        self.end_line();
+        self.allow_blank_line = false;
+
+        for expr in &method.exprs {
+            self.write_expr(expr, indent + 1);
+        }
+
+        self.write_end(indent);
+    }
+
+    // An expr takes one line, except `using`, whose block is indented like a method's body.
+    fn write_expr(&mut self, expr: &Expr, indent: usize) {
+        self.begin_line(get_start(expr), indent);
+
+        if let Expr::Using(using) = expr {
+            let keyword = self.format_span(using.span);
+            let name = self.format_span(using.assignment.name);
+            let value = self.format_expr(&using.assignment.expr);
+            self.output.push_str(&format!("{} {} = {}", keyword, name, value));
+            self.end_line();
+            self.allow_blank_line = false;
+
+            for expr in &using.exprs {
+                self.write_expr(expr, indent + 1);
+            }
+
+            self.write_end(indent);
+            return;
+        }
+
+        let expr = self.format_expr(expr);
+        self.output.push_str(&expr);
+        self.end_line();
+    }
+
+    // The comments before `end` belong to the body, so they are indented one level deeper.
+    fn write_end(&mut self, indent: usize) {
+        let end = self.ends[self.next_end];
+        self.next_end += 1;
+
+        self.write_comments(Some(end), indent + 1);
+        self.write_indent(indent);
+        let end = self.format_span(end);
+        self.output.push_str(end);
+        self.end_line();
+    }
+
+    // Writes the comments before the start of the next code, and the indentation of its line.
+    fn begin_line(&mut self, start: Option<Span>, indent: usize) {
+        if let Some(start) = start {
+            self.write_comments(Some(start), indent);
+
+            if self.allow_blank_line && start.line > self.last_line + 1 {
+                self.output.push('\n');
+            }
+        }
+
+        self.write_indent(indent);
+    }
+
+    fn end_line(&mut self) {
+        if let Some(comment) = self.comments.get(self.next_comment).cloned() {
+            if comment.line == self.last_line {
+                let comment = self.format_span(comment);
+                self.output.push(' ');
+                self.output.push_str(comment);
+                self.next_comment += 1;
+            }
+        }
+
+        self.output.push('\n');
+        self.allow_blank_line = true;
+    }
+
+    fn write_comments(&mut self, before: Option<Span>, indent: usize) {
+        while let Some(comment) = self.comments.get(self.next_comment).cloned() {
+            if let Some(before) = before {
+                if (comment.line, comment.col) >= (before.line, before.col) {
+                    break;
+                }
+            }
+
+            if self.allow_blank_line && comment.line > self.last_line + 1 {
+                self.output.push('\n');
+            }
+
+            self.next_comment += 1;
+            self.write_indent(indent);
+            let comment = self.format_span(comment);
+            self.output.push_str(comment);
+            self.output.push('\n');
+            self.allow_blank_line = true;
+        }
+    }
+
+    fn write_blank_line(&mut self) {
+        self.output.push('\n');
+        self.allow_blank_line = false;
+    }
+
+    fn write_indent(&mut self, indent: usize) {
+        for _ in 0..indent {
+            self.output.push_str(INDENT);
+        }
+    }
+
+    fn format_span<'s>(&mut self, span: Span<'s>) -> &'s str {
+        self.last_line = max(self.last_line, span.line + span.fragment.matches('\n').count());
+        span.fragment
+    }
+
+    fn format_params(&mut self, params: &[Param]) -> String {
+        if params.is_empty() {
+            return String::new();
+        }
+
+        let params = params.iter().map(|p| self.format_param(p)).collect::<Vec<_>>();
+        format!("({})", params.join(", "))
+    }
+
+    fn format_param(&mut self, param: &Param) -> String {
+        let name = self.format_span(param.name.unwrap());
+        let tpe = self.format_span(param.tpe.span.unwrap());
+        format!("{}{}: {}", name, if param.is_varargs { "..." } else { "" }, tpe)
+    }
+
+    fn format_field(&mut self, field: &Param) -> String {
+        let param = self.format_param(field);
+        let value = match &field.value_opt {
+            Some(value) => format!(" = {}", self.format_expr(value)),
+            None => String::new(),
+        };
+
+        format!("{} {}{}", if field.is_mutable { "var" } else { "val" }, param, value)
+    }
+
+    fn format_exprs(&mut self, exprs: &[Expr]) -> String {
+        exprs.iter().map(|e| self.format_expr(e)).collect::<Vec<_>>().join(", ")
+    }
+
+    // There are no parentheses for grouping, so every tree that the parser builds is written back without them.
+    fn format_expr(&mut self, expr: &Expr) -> String {
+        match expr {
+            Expr::Assignment(a) => {
+                let name = self.format_span(a.name);
+                format!("{} = {}", name, self.format_expr(&a.expr))
+            },
+            Expr::Boolean(b) => self.format_span(b.span).to_string(),
+            Expr::Char(c) => self.format_span(c.span).to_string(),
+            Expr::Float(f) => self.format_span(f.span).to_string(),
+            Expr::Int(i) => self.format_span(i.span).to_string(),
+            Expr::String(s) => self.format_span(s.span).to_string(),
+            Expr::Interpolation(i) => self.format_span(i.span).to_string(),
+            Expr::Identifier(i) => self.format_span(i.name.unwrap()).to_string(),
+            Expr::Invoke(i) => {
+                let prefix = if let Some(class_name) = i.static_invoker_opt {
+                    format!("{}.", self.format_span(class_name))
+                } else if let Some(invoker) = &i.invoker_opt {
+                    if i.name.fragment == "==" {
+                        let left = self.format_expr(invoker);
+                        let operator = self.format_span(i.name);
+                        return format!("{} {} {}", left, operator, self.format_exprs(&i.args));
+                    }
+                    format!("{}.", self.format_expr(invoker))
+                } else {
+                    String::new()
+                };
+                let name = self.format_span(i.name);
+                format!("{}{}({})", prefix, name, self.format_exprs(&i.args))
+            },
+            Expr::LogicalOperation(l) => {
+                let left = self.format_expr(&l.left);
+                let operator = self.format_span(l.operator);
+                format!("{} {} {}", left, operator, self.format_expr(&l.right))
+            },
+            Expr::MemberAccess(m) => {
+                let parent = self.format_expr(&m.parent);
+                format!("{}.{}", parent, self.format_span(m.name.unwrap()))
+            },
+            Expr::MemberAssignment(m) => {
+                let parent = self.format_expr(&m.member.parent);
+                let name = self.format_span(m.member.name.unwrap());
+                format!("{}.{} = {}", parent, name, self.format_expr(&m.expr))
+            },
+            Expr::NewInstance(n) => {
+                let name = self.format_span(n.name_opt.unwrap());
+                format!("{}({})", name, self.format_exprs(&n.args))
+            },
+            Expr::SelfInstance(s) => self.format_span(s.span.unwrap()).to_string(),
+            other => panic!("Unable to format {:?}, which is only built by the analysis", other),
+        }
+    }
+}
+
+enum Member<'r, 'a> {
+    Field(&'r Param<'a>),
+    Method(&'r Method<'a>),
+}
+
+// Returns the leftmost span of the expression, which is where its line starts.
+fn get_start<'a>(expr: &Expr<'a>) -> Option<Span<'a>> {
+    match expr {
+        Expr::Assignment(a) => Some(a.name),
+        Expr::Boolean(b) => Some(b.span),
+        Expr::Char(c) => Some(c.span),
+        Expr::Float(f) => Some(f.span),
+        Expr::Int(i) => Some(i.span),
+        Expr::String(s) => Some(s.span),
+        Expr::Interpolation(i) => Some(i.span),
+        Expr::Identifier(i) => i.name,
+        Expr::Invoke(i) => match (i.static_invoker_opt, &i.invoker_opt) {
+            (Some(class_name), _) => Some(class_name),
+            (None, Some(invoker)) => get_start(invoker),
+            (None, None) => Some(i.name),
+        },
+        Expr::LogicalOperation(l) => get_start(&l.left),
+        Expr::MemberAccess(m) => get_start(&m.parent),
+        Expr::MemberAssignment(m) => get_start(&m.member.parent),
+        Expr::NewInstance(n) => n.name_opt,
+        Expr::SelfInstance(s) => s.span,
+        Expr::Using(u) => Some(u.span),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fmt::apply;
+    use parse;
+
+    fn format(content: &str) -> String {
+        let file = unwrap!(Ok, parse::apply(content, ""));
+        apply(&file)
+    }
+
+    #[test]
+    fn test_format() {
+        let content = r#"
+/// A counter.
+class   Counter( start :Int ,step: Int )
+      var count: Int = 0 // The current count.
+   val name: String
+
+
+
+     def inc( n:Int ) :Int
+  // Adds n.
+        count = add(count,n)
+
+    self.count = count
+   /* Nothing
+      else. */
+  end
+   static def zero(): Counter
+ Counter(0,1)
+      end
+end
+// Entry.
+def main: Int
+  a==b&&c||d
+  Counter.zero().inc(1)
+     "x#{count}"
+    using  c = Counter(0,1)
+  // Increments.
+          c.inc(1)
+      end
+end
+"#;
+        let expected = r#"/// A counter.
+class Counter(start: Int, step: Int)
+  var count: Int = 0 // The current count.
+  val name: String
+
+  def inc(n: Int): Int
+    // Adds n.
+    count = add(count, n)
+
+    self.count = count
+    /* Nothing
+      else. */
+  end
+
+  static def zero: Counter
+    Counter(0, 1)
+  end
+end
+
+// Entry.
+def main: Int
+  a == b && c || d
+  Counter.zero().inc(1)
+  "x#{count}"
+  using c = Counter(0, 1)
+    // Increments.
+    c.inc(1)
+  end
+end
+"#;
+        assert_eq!(format(content), expected);
+        assert_eq!(format(expected), expected);
+    }
+
+    #[test]
+    fn test_trailing_comments() {
+        let content = r#"
+def main: Int // The entry.
+  1
+end // The end.
+
+// The last comment.
+"#;
+        assert_eq!(format(content), content.trim_start());
+    }
+}
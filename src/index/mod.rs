@@ -90,7 +90,9 @@ end
                 params: vec![],
                 exprs: vec![],
                 return_type: Type { span: Some(span2(1, 13, "Number", files.get(0).unwrap().deref())), class_def: None },
+                is_static: false,
                 parent_class: None,
+                doc_comments: vec![],
                 llvm: Cell::new(None)
             }
         );
@@ -99,16 +101,20 @@ end
             &parse::tree::Class {
                 name: span2(1, 7, "Test", files.get(1).unwrap().deref()),
                 params: vec![],
+                fields: vec![],
                 methods: vec![
                     parse::tree::Method {
                         name: span2(2, 7, "test", files.get(1).unwrap().deref()),
                         params: vec![],
                         exprs: vec![],
                         return_type: Type { span: Some(span2(2, 15, "Number", files.get(1).unwrap().deref())), class_def: None },
+                        is_static: false,
                         parent_class: None,
+                        doc_comments: vec![],
                         llvm: Cell::new(None),
                     }
                 ],
+                doc_comments: vec![],
                 llvm: Cell::new(None),
                 llvm_native: Cell::new(None),
             }
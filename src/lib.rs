@@ -2,6 +2,8 @@
 #![allow(unused_variables)]
 
 extern crate inkwell;
+#[macro_use]
+extern crate serde_json;
 
 use parse::tree::CompilationUnit;
 
@@ -12,8 +14,12 @@ pub mod macros;
 pub mod test_common;
 
 pub mod analyse;
+pub mod doc;
+pub mod dump;
 pub mod emit;
+pub mod fmt;
 pub mod index;
+pub mod lsp;
 pub mod tokenize;
 pub mod parse;
 
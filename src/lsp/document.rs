@@ -0,0 +1,384 @@
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+
+use {LilitFile, analyse, index, parse};
+use analyse::scope::Scope;
+use analyse::tpe::GetType;
+use index::tree::Root;
+use parse::tree::{Class, CompilationUnitItem, Expr, IdentifierSource, InterpolationPart, Method, Param, Type};
+use tokenize::span::Span;
+
+// Both the start and the end are counted like spans, from 1. The end is exclusive.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub col: usize,
+    pub end_line: usize,
+    pub end_col: usize,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(span: Span, message: String) -> Diagnostic {
+        let (end_line, end_col) = span.get_end();
+        Diagnostic { line: span.line, col: span.col, end_line, end_col, message }
+    }
+}
+
+// A span in the document, which can be hovered or navigated from.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Symbol<'def> {
+    pub span: Span<'def>,
+    pub definition: Option<Span<'def>>,
+    pub hover: Option<String>,
+}
+
+// The file is parsed and analysed as much as possible. An error only stops the analysis of the method or the class member
+// that contains it, so the symbols elsewhere are still resolved.
+pub struct Document {
+    pub content: String,
+    pub diagnostics: Vec<Diagnostic>,
+    root: Option<Root<'static>>,
+    file: Option<Pin<Box<LilitFile<'static>>>>,
+}
+
+impl Document {
+    pub fn compile(uri: &str, content: &str) -> Document {
+        let mut diagnostics = vec![];
+
+        let mut file = match parse::apply(content, uri) {
+            Ok(file) => file,
+            Err(span) => {
+                let span = relocate(content, span);
+                diagnostics.push(Diagnostic::new(span, "Unable to parse the code".to_string()));
+                return Document { content: content.to_string(), diagnostics, root: None, file: None };
+            },
+        };
+
+        let root = index::build(&[file.deref()]);
+
+        for error in analyse::check(&mut [file.deref_mut()], &root) {
+            diagnostics.push(Diagnostic::new(error.span, error.message));
+        }
+
+        Document { content: content.to_string(), diagnostics, root: Some(root), file: Some(file) }
+    }
+
+    pub fn get_items(&self) -> &[CompilationUnitItem<'static>] {
+        match &self.file {
+            Some(file) => &file.unit.items,
+            None => &[],
+        }
+    }
+
+    // Returns the innermost symbol that contains the position. Both the line and the column start at 1.
+    pub fn find_symbol(&self, line: usize, col: usize) -> Option<Symbol<'static>> {
+        let root = self.root.as_ref()?;
+        let mut symbols = vec![];
+        let mut scope = Scope { levels: vec![] };
+        scope.enter_root(root);
+
+        for item in self.get_items() {
+            match item {
+                CompilationUnitItem::Class(class) => collect_class(class, &scope, &mut symbols),
+                CompilationUnitItem::Method(method) => collect_method(method, &scope, &mut symbols),
+            }
+        }
+
+        symbols
+            .into_iter()
+            .filter(|s| s.span.line == line && s.span.col <= col && col < s.span.col + s.span.fragment.chars().count())
+            .min_by_key(|s| s.span.fragment.chars().count())
+    }
+}
+
+fn collect_class<'def>(class: &Class<'def>, scope: &Scope<'def>, symbols: &mut Vec<Symbol<'def>>) {
+    symbols.push(Symbol { span: class.name, definition: Some(class.name), hover: Some(describe_class(class)) });
+
+    for param in class.get_members() {
+        collect_param(param, scope, symbols);
+    }
+
+    for method in &class.methods {
+        collect_method(method, scope, symbols);
+    }
+}
+
+fn collect_method<'def>(method: &Method<'def>, scope: &Scope<'def>, symbols: &mut Vec<Symbol<'def>>) {
+    symbols.push(Symbol { span: method.name, definition: Some(method.name), hover: Some(describe_method(method)) });
+
+    for param in &method.params {
+        collect_param(param, scope, symbols);
+    }
+
+    collect_type(&method.return_type, symbols);
+
+    for expr in &method.exprs {
+        collect_expr(expr, scope, symbols);
+    }
+}
+
+fn collect_param<'def>(param: &Param<'def>, scope: &Scope<'def>, symbols: &mut Vec<Symbol<'def>>) {
+    // The implicit receiver of an instance method has no name and no type in the code.
+    let name = match param.name {
+        Some(name) => name,
+        None => return,
+    };
+
+    symbols.push(Symbol { span: name, definition: Some(name), hover: Some(describe_param(param)) });
+    collect_type(&param.tpe, symbols);
+
+    if let Some(value) = &param.value_opt {
+        collect_expr(value, scope, symbols);
+    }
+}
+
+fn collect_type<'def>(tpe: &Type<'def>, symbols: &mut Vec<Symbol<'def>>) {
+    if let Some(span) = tpe.span {
+        let class = tpe.class_def.map(|c| unsafe { &*c });
+        symbols.push(Symbol {
+            span,
+            definition: class.map(|c| c.name),
+            hover: class.map(describe_class),
+        });
+    }
+}
+
+fn collect_expr<'def>(expr: &Expr<'def>, scope: &Scope<'def>, symbols: &mut Vec<Symbol<'def>>) {
+    match expr {
+        Expr::Assignment(a) => {
+            symbols.push(Symbol {
+                span: a.name,
+                definition: Some(a.name),
+                hover: a.tpe.map(|c| format!("{}: {}", a.name.fragment, unsafe { &*c }.name.fragment)),
+            });
+            collect_expr(&a.expr, scope, symbols);
+        },
+        Expr::Boolean(b) => symbols.push(Symbol { span: b.span, definition: None, hover: describe_type(expr, scope) }),
+        Expr::Char(c) => symbols.push(Symbol { span: c.span, definition: None, hover: describe_type(expr, scope) }),
+        Expr::Float(f) => symbols.push(Symbol { span: f.span, definition: None, hover: describe_type(expr, scope) }),
+        Expr::Int(i) => symbols.push(Symbol { span: i.span, definition: None, hover: describe_type(expr, scope) }),
+        Expr::String(s) => symbols.push(Symbol { span: s.span, definition: None, hover: describe_type(expr, scope) }),
+        Expr::Identifier(i) => {
+            if let Some(name) = i.name {
+                let definition = match &i.source {
+                    Some(IdentifierSource::Assignment(a)) => Some(unsafe { &**a }.name),
+                    Some(IdentifierSource::Param(p)) => unsafe { &**p }.name,
+                    Some(IdentifierSource::ClassParam(m)) => m.param_def.and_then(|p| unsafe { &*p }.name),
+                    None => None,
+                };
+                symbols.push(Symbol {
+                    span: name,
+                    definition,
+                    hover: describe_type(expr, scope).map(|t| format!("{}: {}", name.fragment, t)),
+                });
+            }
+        },
+        Expr::Interpolation(i) => {
+            for part in &i.parts {
+                if let InterpolationPart::Expr(e) = part {
+                    collect_expr(e, scope, symbols);
+                }
+            }
+        },
+        Expr::Invoke(i) => {
+            let method = i.method_def.map(|m| unsafe { &*m });
+            symbols.push(Symbol {
+                span: i.name,
+                definition: method.map(|m| m.name),
+                hover: method.map(describe_method),
+            });
+
+            if let Some(class_name) = i.static_invoker_opt {
+                let class = method.and_then(|m| m.parent_class).map(|c| unsafe { &*c });
+                symbols.push(Symbol { span: class_name, definition: class.map(|c| c.name), hover: class.map(describe_class) });
+            }
+
+            if let Some(invoker) = &i.invoker_opt {
+                collect_expr(invoker, scope, symbols);
+            }
+
+            for arg in &i.args {
+                collect_expr(arg, scope, symbols);
+            }
+        },
+        Expr::LogicalOperation(l) => {
+            collect_expr(&l.left, scope, symbols);
+            collect_expr(&l.right, scope, symbols);
+        },
+        Expr::MemberAccess(m) => {
+            if let Some(name) = m.name {
+                let param = m.param_def.map(|p| unsafe { &*p });
+                symbols.push(Symbol { span: name, definition: param.and_then(|p| p.name), hover: param.map(describe_param) });
+            }
+            collect_expr(&m.parent, scope, symbols);
+        },
+        Expr::MemberAssignment(m) => {
+            collect_expr(&Expr::MemberAccess(Box::new(m.member.clone())), scope, symbols);
+            collect_expr(&m.expr, scope, symbols);
+        },
+        Expr::NewInstance(n) => {
+            if let Some(name) = n.name_opt {
+                let class = n.class_def.map(|c| unsafe { &*c });
+                symbols.push(Symbol { span: name, definition: class.map(|c| c.name), hover: class.map(describe_class) });
+            }
+
+            for arg in &n.args {
+                collect_expr(arg, scope, symbols);
+            }
+        },
+        Expr::SelfInstance(s) => {
+            if let Some(span) = s.span {
+                symbols.push(Symbol { span, definition: None, hover: describe_type(expr, scope) });
+            }
+        },
+        Expr::Using(u) => {
+            collect_expr(&Expr::Assignment(Box::new(u.assignment.clone())), scope, symbols);
+            for e in &u.exprs {
+                collect_expr(e, scope, symbols);
+            }
+        },
+        Expr::NativeBool(_) | Expr::NativeChar(_) | Expr::NativeFloat(_) | Expr::NativeInt(_) | Expr::NativeString(_) => (),
+    }
+}
+
+// The expression has no type when it hasn't been analysed, e.g. when it comes after an error.
+fn describe_type<'def>(expr: &Expr<'def>, scope: &Scope<'def>) -> Option<String> {
+    expr.find_type(scope).map(|class| class.name.fragment.to_string())
+}
+
+fn describe_class(class: &Class) -> String {
+    let params = class.params.iter().map(describe_param).collect::<Vec<_>>();
+    let signature = if params.is_empty() {
+        format!("class {}", class.name.fragment)
+    } else {
+        format!("class {}({})", class.name.fragment, params.join(", "))
+    };
+
+    with_doc(signature, class.get_doc())
+}
+
+fn describe_method(method: &Method) -> String {
+    let params = method.params.iter().filter(|p| p.name.is_some()).map(describe_param).collect::<Vec<_>>();
+    let signature = format!(
+        "{}def {}{}: {}",
+        if method.is_static { "static " } else { "" },
+        method.name.fragment,
+        if params.is_empty() { String::new() } else { format!("({})", params.join(", ")) },
+        method.return_type.span.map(|s| s.fragment).unwrap_or("")
+    );
+
+    with_doc(signature, method.get_doc())
+}
+
+fn describe_param(param: &Param) -> String {
+    format!(
+        "{}{}: {}",
+        param.name.map(|s| s.fragment).unwrap_or("self"),
+        if param.is_varargs { "..." } else { "" },
+        param.tpe.span.map(|s| s.fragment).unwrap_or("")
+    )
+}
+
+fn with_doc(signature: String, doc: String) -> String {
+    if doc.is_empty() {
+        signature
+    } else {
+        format!("{}\n\n{}", signature, doc)
+    }
+}
+
+// A parse error points into the file that has been dropped, so it's moved to the same place in the content. The error
+// of the tokenizer spans the rest of the file, so only its first line is kept.
+fn relocate<'a>(content: &'a str, span: Span) -> Span<'a> {
+    let text = content.lines().nth(span.line.saturating_sub(1)).unwrap_or("");
+    let start = text.char_indices().nth(span.col.saturating_sub(1)).map_or(text.len(), |(index, _)| index);
+    let rest = &text[start..];
+    let end = rest.char_indices()
+        .map(|(index, c)| index + c.len_utf8())
+        .take_while(|end| *end <= span.fragment.len())
+        .last()
+        .unwrap_or(0);
+
+    Span { line: span.line, col: span.col, fragment: &rest[..end], file: std::ptr::null() }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp::document::{Document, Diagnostic};
+
+    const CONTENT: &str = r#"class Native__Int
+end
+
+/// A whole number.
+class Int(underlying: Native__Int)
+end
+
+def main: Int
+  a = 1
+  a
+  b
+end
+"#;
+
+    #[test]
+    fn test_partial() {
+        let document = Document::compile("", CONTENT);
+
+        assert_eq!(
+            document.diagnostics,
+            vec![Diagnostic { line: 11, col: 3, end_line: 11, end_col: 4, message: "Unable to find the identifier b".to_string() }]
+        );
+
+        let symbol = document.find_symbol(10, 3).unwrap();
+        assert_eq!(symbol.hover, Some("a: Int".to_string()));
+        assert_eq!(symbol.definition.map(|s| (s.line, s.col)), Some((9, 3)));
+
+        let symbol = document.find_symbol(8, 12).unwrap();
+        assert_eq!(symbol.hover, Some("class Int(underlying: Native__Int)\n\nA whole number.".to_string()));
+        assert_eq!(symbol.definition.map(|s| (s.line, s.col)), Some((5, 7)));
+
+        assert_eq!(document.find_symbol(11, 3).unwrap().hover, None);
+    }
+
+    #[test]
+    fn test_several_errors() {
+        let content = r#"class Native__Int
+end
+
+class Int(underlying: Native__Int)
+end
+
+def first: Int
+  missing()
+end
+
+def second: Int
+  1
+  Float()
+end
+"#;
+        let document = Document::compile("", content);
+
+        assert_eq!(
+            document.diagnostics,
+            vec![
+                Diagnostic { line: 8, col: 3, end_line: 8, end_col: 10, message: "Unable to find the method missing".to_string() },
+                Diagnostic { line: 13, col: 3, end_line: 13, end_col: 8, message: "Unable to find the class Float".to_string() },
+            ]
+        );
+        assert_eq!(document.find_symbol(12, 3).unwrap().hover, Some("Int".to_string()));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let document = Document::compile("", "class A\n  def\nend");
+
+        assert_eq!(
+            document.diagnostics,
+            vec![Diagnostic { line: 1, col: 1, end_line: 1, end_col: 6, message: "Unable to parse the code".to_string() }]
+        );
+        assert_eq!(document.get_items().len(), 0);
+        assert_eq!(document.find_symbol(1, 7), None);
+    }
+}
@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+use serde_json::Value as Json;
+
+use lsp::document::Document;
+use parse::tree::{Class, CompilationUnitItem, Method};
+use tokenize::span::Span;
+
+pub mod document;
+
+const SYMBOL_KIND_CLASS: u64 = 5;
+const SYMBOL_KIND_METHOD: u64 = 6;
+const SYMBOL_KIND_FIELD: u64 = 8;
+const SYMBOL_KIND_FUNCTION: u64 = 12;
+
+// Serves the language server protocol over stdin and stdout until the client sends `exit`.
+// Documents are synced in full, and each change re-compiles the document.
+pub fn apply<R: BufRead, W: Write>(mut input: R, mut output: W) -> io::Result<()> {
+    let mut server = Server { documents: HashMap::new() };
+
+    while let Some(message) = read_message(&mut input)? {
+        if message["method"] == "exit" {
+            break;
+        }
+
+        for response in server.handle(&message) {
+            write_message(&mut output, &response)?;
+        }
+    }
+
+    Ok(())
+}
+
+struct Server {
+    documents: HashMap<String, Document>,
+}
+
+impl Server {
+    fn handle(&mut self, message: &Json) -> Vec<Json> {
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or("").to_string();
+
+        let result = match message["method"].as_str().unwrap_or("") {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "definitionProvider": true,
+                    "hoverProvider": true,
+                    "documentSymbolProvider": true
+                },
+                "serverInfo": { "name": "lilit" }
+            }),
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or("");
+                return vec![self.open(uri, text)];
+            },
+            "textDocument/didChange" => {
+                let changes = params["contentChanges"].as_array();
+                let text = changes.and_then(|c| c.last()).and_then(|c| c["text"].as_str()).unwrap_or("");
+                return vec![self.open(uri, text)];
+            },
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                return vec![publish_diagnostics(&uri, vec![])];
+            },
+            "textDocument/definition" => self.find_definition(&uri, &params["position"]),
+            "textDocument/hover" => self.hover(&uri, &params["position"]),
+            "textDocument/documentSymbol" => self.get_document_symbols(&uri),
+            "shutdown" => Json::Null,
+            method => {
+                if message["id"].is_null() {
+                    return vec![];
+                }
+
+                return vec![json!({
+                    "jsonrpc": "2.0",
+                    "id": message["id"],
+                    "error": { "code": -32601, "message": format!("Unsupported method {}", method) }
+                })];
+            },
+        };
+
+        if message["id"].is_null() {
+            vec![]
+        } else {
+            vec![json!({ "jsonrpc": "2.0", "id": message["id"], "result": result })]
+        }
+    }
+
+    fn open(&mut self, uri: String, text: &str) -> Json {
+        let document = Document::compile(&uri, text);
+        let diagnostics = document.diagnostics
+            .iter()
+            .map(|d| {
+                let range = json!({
+                    "start": to_position(&document.content, d.line, d.col),
+                    "end": to_position(&document.content, d.end_line, d.end_col)
+                });
+                json!({ "range": range, "severity": 1, "source": "lilit", "message": d.message })
+            })
+            .collect();
+
+        let notification = publish_diagnostics(&uri, diagnostics);
+        self.documents.insert(uri, document);
+        notification
+    }
+
+    fn find_definition(&self, uri: &str, position: &Json) -> Json {
+        let document = match self.documents.get(uri) {
+            Some(document) => document,
+            None => return Json::Null,
+        };
+
+        match find_symbol(document, position).and_then(|s| s.definition) {
+            Some(span) => json!({ "uri": uri, "range": to_range(&document.content, span) }),
+            None => Json::Null,
+        }
+    }
+
+    fn hover(&self, uri: &str, position: &Json) -> Json {
+        let document = match self.documents.get(uri) {
+            Some(document) => document,
+            None => return Json::Null,
+        };
+
+        match find_symbol(document, position) {
+            Some(symbol) => match symbol.hover {
+                Some(hover) => json!({
+                    "contents": { "kind": "markdown", "value": format_hover(&hover) },
+                    "range": to_range(&document.content, symbol.span)
+                }),
+                None => Json::Null,
+            },
+            None => Json::Null,
+        }
+    }
+
+    fn get_document_symbols(&self, uri: &str) -> Json {
+        let document = match self.documents.get(uri) {
+            Some(document) => document,
+            None => return json!([]),
+        };
+
+        let symbols = document.get_items()
+            .iter()
+            .map(|item| match item {
+                CompilationUnitItem::Class(class) => build_class_symbol(&document.content, class),
+                CompilationUnitItem::Method(method) => build_symbol(&document.content, method.name, SYMBOL_KIND_FUNCTION, vec![]),
+            })
+            .collect::<Vec<_>>();
+
+        Json::Array(symbols)
+    }
+}
+
+fn build_class_symbol(content: &str, class: &Class) -> Json {
+    let mut children = vec![];
+
+    for field in &class.fields {
+        if let Some(name) = field.name {
+            children.push(build_symbol(content, name, SYMBOL_KIND_FIELD, vec![]));
+        }
+    }
+
+    for method in &class.methods {
+        children.push(build_symbol(content, method.name, SYMBOL_KIND_METHOD, vec![]));
+    }
+
+    build_symbol(content, class.name, SYMBOL_KIND_CLASS, children)
+}
+
+fn build_symbol(content: &str, name: Span, kind: u64, children: Vec<Json>) -> Json {
+    let range = to_range(content, name);
+    json!({ "name": name.fragment, "kind": kind, "range": range, "selectionRange": range, "children": children })
+}
+
+fn find_symbol(document: &Document, position: &Json) -> Option<document::Symbol<'static>> {
+    let line = position["line"].as_u64()? as usize;
+    let character = position["character"].as_u64()? as usize;
+    let col = to_col(&document.content, line, character);
+
+    document.find_symbol(line + 1, col)
+}
+
+// The hover shows the signature as code and the doc, if any, as text.
+fn format_hover(hover: &str) -> String {
+    let mut parts = hover.splitn(2, "\n\n");
+    let signature = parts.next().unwrap_or("");
+
+    match parts.next() {
+        Some(doc) => format!("```lilit\n{}\n```\n\n{}", signature, doc),
+        None => format!("```lilit\n{}\n```", signature),
+    }
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Json>) -> Json {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics }
+    })
+}
+
+fn to_range(content: &str, span: Span) -> Json {
+    let (end_line, end_col) = span.get_end();
+
+    json!({ "start": to_position(content, span.line, span.col), "end": to_position(content, end_line, end_col) })
+}
+
+// Spans count lines and chars from 1, while LSP counts lines from 0 and chars in UTF-16 code units.
+fn to_position(content: &str, line: usize, col: usize) -> Json {
+    let text = content.lines().nth(line.saturating_sub(1)).unwrap_or("");
+    let character: usize = text.chars().take(col.saturating_sub(1)).map(|c| c.len_utf16()).sum();
+
+    json!({ "line": line.saturating_sub(1), "character": character })
+}
+
+fn to_col(content: &str, line: usize, character: usize) -> usize {
+    let text = content.lines().nth(line).unwrap_or("");
+    let mut units = 0;
+    let mut col = 1;
+
+    for c in text.chars() {
+        if units >= character {
+            break;
+        }
+        units += c.len_utf16();
+        col += 1;
+    }
+
+    col
+}
+
+fn read_message<R: BufRead>(input: &mut R) -> io::Result<Option<Json>> {
+    let mut content_length = None;
+
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+
+        let header = header.trim();
+        if header.is_empty() {
+            break;
+        }
+
+        if header.to_lowercase().starts_with("content-length:") {
+            content_length = header["content-length:".len()..].trim().parse::<usize>().ok();
+        }
+    }
+
+    let mut body = vec![0; content_length.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing Content-Length"))?];
+    input.read_exact(&mut body)?;
+
+    serde_json::from_slice(&body).map(Some).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn write_message<W: Write>(output: &mut W, message: &Json) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use serde_json::Value as Json;
+
+    use lsp::{apply, read_message};
+
+    fn frame(message: Json) -> String {
+        let body = message.to_string();
+        format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+    }
+
+    #[test]
+    fn test_session() {
+        let uri = "file:///main.lilit";
+        let text = "class Native__Int\nend\n\nclass Int(underlying: Native__Int)\nend\n\ndef main: Int\n  1\n  a\nend\n";
+        let input = vec![
+            frame(json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} })),
+            frame(json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} })),
+            frame(json!({ "jsonrpc": "2.0", "method": "textDocument/didOpen", "params": { "textDocument": { "uri": uri, "text": text } } })),
+            frame(json!({ "jsonrpc": "2.0", "id": 2, "method": "textDocument/definition", "params": { "textDocument": { "uri": uri }, "position": { "line": 6, "character": 11 } } })),
+            frame(json!({ "jsonrpc": "2.0", "id": 3, "method": "textDocument/hover", "params": { "textDocument": { "uri": uri }, "position": { "line": 7, "character": 2 } } })),
+            frame(json!({ "jsonrpc": "2.0", "id": 4, "method": "textDocument/documentSymbol", "params": { "textDocument": { "uri": uri } } })),
+            frame(json!({ "jsonrpc": "2.0", "id": 5, "method": "shutdown" })),
+            frame(json!({ "jsonrpc": "2.0", "method": "exit" })),
+        ].concat();
+
+        let mut output = vec![];
+        apply(Cursor::new(input), &mut output).unwrap();
+
+        let mut output = Cursor::new(output);
+        let mut messages = vec![];
+        while let Some(message) = read_message(&mut output).unwrap() {
+            messages.push(message);
+        }
+
+        assert_eq!(messages.len(), 6);
+        assert_eq!(messages[0]["result"]["capabilities"]["hoverProvider"], json!(true));
+
+        assert_eq!(messages[1]["method"], json!("textDocument/publishDiagnostics"));
+        let diagnostics = messages[1]["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["range"], json!({ "start": { "line": 8, "character": 2 }, "end": { "line": 8, "character": 3 } }));
+
+        assert_eq!(
+            messages[2],
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "result": {
+                    "uri": uri,
+                    "range": { "start": { "line": 3, "character": 6 }, "end": { "line": 3, "character": 9 } }
+                }
+            })
+        );
+        assert_eq!(messages[3]["result"]["contents"]["value"], json!("```lilit\nInt\n```"));
+        assert_eq!(messages[4]["result"][2]["name"], json!("main"));
+        assert_eq!(messages[4]["result"][2]["kind"], json!(12));
+        assert_eq!(messages[5], json!({ "jsonrpc": "2.0", "id": 5, "result": null }));
+    }
+}
@@ -1,51 +1,322 @@
-
 extern crate lilit;
 extern crate inkwell;
+extern crate serde_json;
 
-use lilit::{analyse, emit, index, parse};
+use lilit::{analyse, doc, dump, emit, fmt, index, lsp, parse, tokenize, LilitFile};
+use lilit::analyse::scope::Scope;
+use lilit::parse::tree::CompilationUnit;
+use lilit::emit::optimize::{self, OptLevel};
 use std::env;
-use std::fs::File;
-use std::io::Read;
+use std::fs::{self, File};
+use std::io::{self, Read};
 use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
 use inkwell::targets::{InitializationConfig, Target, TargetMachine, RelocMode, CodeModel, FileType};
-use inkwell::OptimizationLevel;
 use std::path::Path;
+use std::process;
 
 
 fn main() {
-    println!("Lilit 0.1.0\n");
     let args: Vec<String> = env::args().collect();
-    let mut file = File::open(&args[1]).expect("file not found");
+
+    match args.get(1).map(|s| s.as_ref()) {
+        Some("doc") => generate_doc(&args[2..]),
+        Some("fmt") => format_files(&args[2..]),
+        Some("lsp") => {
+            let stdin = io::stdin();
+            let stdout = io::stdout();
+            lsp::apply(stdin.lock(), stdout.lock()).expect("Unable to communicate with the client");
+        },
+        _ => compile(&parse_options(&args[1..])),
+    }
+}
+
+fn read_file(path: &str) -> String {
+    let mut file = File::open(path).expect("file not found");
 
     let mut content = String::new();
     file.read_to_string(&mut content)
         .expect("something went wrong reading the file");
+    content
+}
+
+// Usage: lilit doc [-o <output dir>] <file>...
+fn generate_doc(args: &[String]) {
+    let mut output_dir = "./doc".to_string();
+    let mut paths = vec![];
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_ref() {
+            "-o" => output_dir = args.next().expect("Expect a directory after -o").clone(),
+            path => paths.push(path.to_string()),
+        }
+    }
+
+    if paths.is_empty() {
+        panic!("Usage: lilit doc [-o <output dir>] <file>...");
+    }
+
+    let mut files: Vec<Pin<Box<LilitFile>>> = paths
+        .iter()
+        .map(|path| {
+            let content = read_file(path);
+            match parse::apply(content.trim(), path) {
+                Ok(file) => file,
+                Err(span) => panic!("Unable to parse {} at line {}, column {}", path, span.line, span.col),
+            }
+        })
+        .collect();
+
+    let root = index::build(&files.iter().map(|f| f.deref()).collect::<Vec<_>>());
+    analyse::apply(&mut files.iter_mut().map(|f| f.deref_mut()).collect::<Vec<_>>(), &root);
+
+    doc::apply(&root, Path::new(&output_dir)).expect("Unable to write the docs");
+    println!("Write the docs to {}", output_dir);
+}
+
+// Usage: lilit fmt [--check] <file>...
+// With --check, the files aren't written, and the exit code is 1 when any of them isn't formatted.
+fn format_files(args: &[String]) {
+    let check = args.iter().any(|a| a == "--check");
+    let paths = args.iter().filter(|a| *a != "--check").collect::<Vec<_>>();
+
+    if paths.is_empty() {
+        panic!("Usage: lilit fmt [--check] <file>...");
+    }
+
+    let mut unformatted = false;
+
+    for path in paths {
+        let content = read_file(path);
+        let file = match parse::apply(&content, path) {
+            Ok(file) => file,
+            Err(span) => panic!("Unable to parse {} at line {}, column {}", path, span.line, span.col),
+        };
+        let formatted = fmt::apply(&file);
+
+        if formatted == content {
+            continue;
+        }
+
+        if check {
+            println!("{} isn't formatted", path);
+            unformatted = true;
+        } else {
+            fs::write(path, formatted).expect("Unable to write the file");
+            println!("Format {}", path);
+        }
+    }
+
+    if unformatted {
+        process::exit(1);
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Emit {
+    Tokens,
+    Ast,
+    TypedAst,
+    LlvmIr,
+    Bitcode,
+    Asm,
+    Obj,
+}
+
+impl Emit {
+    fn parse(kind: &str) -> Emit {
+        match kind {
+            "tokens" => Emit::Tokens,
+            "ast" => Emit::Ast,
+            "typed-ast" => Emit::TypedAst,
+            "llvm-ir" => Emit::LlvmIr,
+            "bitcode" => Emit::Bitcode,
+            "asm" => Emit::Asm,
+            "obj" => Emit::Obj,
+            _ => panic!("Unknown --emit kind {}. Expect tokens, ast, typed-ast, llvm-ir, bitcode, asm or obj", kind),
+        }
+    }
+
+    // The JSON forms are written to stdout unless -o is given.
+    fn get_default_output(&self) -> Option<&'static str> {
+        match self {
+            Emit::Tokens | Emit::Ast | Emit::TypedAst => None,
+            Emit::LlvmIr => Some("./output/main.ll"),
+            Emit::Bitcode => Some("./output/main.bc"),
+            Emit::Asm => Some("./output/main.s"),
+            Emit::Obj => Some("./output/main.o"),
+        }
+    }
 
-    compile(content.trim(), &args[1]);
+    fn needs_llvm(&self) -> bool {
+        match self {
+            Emit::Tokens | Emit::Ast | Emit::TypedAst => false,
+            Emit::LlvmIr | Emit::Bitcode | Emit::Asm | Emit::Obj => true,
+        }
+    }
 }
 
-fn compile(content: &str, path: &str) {
-    let mut file = parse::apply(content, path).unwrap();
+struct Options {
+    path: String,
+    emits: Vec<Emit>,
+    output: Option<String>,
+    opt_level: OptLevel,
+    target: Option<String>, // None is the host.
+    cpu: String,
+    features: String,
+    is_debug: bool,
+}
+
+// Usage: lilit [-O0|-O1|-O2|-O3|-Os] [-g] [--emit=<kind>[,<kind>...]] [-o <output>]
+//              [--target <triple>] [--cpu <cpu>] [--features <features>] <file>
+// The kinds are tokens, ast, typed-ast, llvm-ir, bitcode, asm and obj. The defaults are -O0, obj, the host's triple
+// and the generic CPU. The features are LLVM's, e.g. `+neon,-fp-armv8`.
+fn parse_options(args: &[String]) -> Options {
+    let mut path = None;
+    let mut emits = vec![];
+    let mut output = None;
+    let mut opt_level = OptLevel::O0;
+    let mut target = None;
+    let mut cpu = "generic".to_string();
+    let mut features = String::new();
+    let mut is_debug = false;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if arg.starts_with("--emit=") {
+            emits.extend(arg["--emit=".len()..].split(',').map(Emit::parse));
+        } else if let Some(level) = OptLevel::parse(arg) {
+            opt_level = level;
+        } else if arg.starts_with("-O") {
+            panic!("Unknown optimization level {}. Expect -O0, -O1, -O2, -O3 or -Os", arg);
+        } else if arg == "-g" {
+            if !cfg!(feature = "debug-info") {
+                panic!("-g isn't supported because lilit is built without the debug-info feature, which needs llvm-config");
+            }
+            is_debug = true;
+        } else if arg == "-o" {
+            output = Some(args.next().expect("Expect a path after -o").clone());
+        } else if arg == "--target" {
+            target = Some(args.next().expect("Expect a triple after --target, e.g. aarch64-unknown-linux-gnu").clone());
+        } else if arg == "--cpu" {
+            cpu = args.next().expect("Expect a CPU after --cpu, e.g. cortex-a72").clone();
+        } else if arg == "--features" {
+            features = args.next().expect("Expect the features after --features, e.g. +neon").clone();
+        } else {
+            path = Some(arg.clone());
+        }
+    }
 
-    println!("---- Code ----");
-    println!("{}\n", content);
+    if emits.is_empty() {
+        emits.push(Emit::Obj);
+    }
+
+    if output.is_some() && emits.len() > 1 {
+        panic!("-o can only be used with one --emit kind");
+    }
+
+    Options {
+        path: path.expect("Usage: lilit [-O0|-O1|-O2|-O3|-Os] [-g] [--emit=<kind>[,<kind>...]] [-o <output>] [--target <triple>] [--cpu <cpu>] [--features <features>] <file>"),
+        emits,
+        output,
+        opt_level,
+        target,
+        cpu,
+        features,
+        is_debug,
+    }
+}
+
+fn get_output(options: &Options, emit: Emit) -> Option<&str> {
+    options.output.as_ref().map(|o| o.as_ref()).or(emit.get_default_output())
+}
+
+fn write_output(options: &Options, emit: Emit, content: &str) {
+    match get_output(options, emit) {
+        Some(path) => fs::write(path, content).expect("Unable to write the output"),
+        None => println!("{}", content),
+    }
+}
+
+fn compile(options: &Options) {
+    let content = read_file(&options.path);
+    let content = content.trim();
+
+    if options.emits.contains(&Emit::Tokens) {
+        // A span points at its file, e.g. for the path in an error, so the tokens get a file of their own, which is
+        // there even when the content doesn't parse.
+        let file = LilitFile { unit: CompilationUnit { items: vec![] }, content: content.to_string(), path: options.path.clone() };
+        let tokens = match tokenize::apply(content, &file) {
+            Ok(tokens) => tokens,
+            Err(span) => panic!("Unable to tokenize {} at line {}, column {}", options.path, span.line, span.col),
+        };
+        write_output(options, Emit::Tokens, &serde_json::to_string_pretty(&dump::apply_tokens(&tokens)).unwrap());
+    }
+
+    let mut file = match parse::apply(content, &options.path) {
+        Ok(file) => file,
+        Err(span) => panic!("Unable to parse {} at line {}, column {}", options.path, span.line, span.col),
+    };
+
+    if options.emits.contains(&Emit::Ast) {
+        write_output(options, Emit::Ast, &serde_json::to_string_pretty(&dump::apply(&file.unit, None)).unwrap());
+    }
+
+    if !options.emits.iter().any(|e| *e == Emit::TypedAst || e.needs_llvm()) {
+        return;
+    }
 
     let root = index::build(&[file.deref()]);
 
     analyse::apply(&mut [file.deref_mut()], &root);
 
-    let module = emit::apply(&[file.deref()]);
-    module.print_to_stderr();
+    if options.emits.contains(&Emit::TypedAst) {
+        let mut scope = Scope { levels: vec![] };
+        scope.enter_root(&root);
+        write_output(options, Emit::TypedAst, &serde_json::to_string_pretty(&dump::apply(&file.unit, Some(&scope))).unwrap());
+    }
 
-    Target::initialize_native(&InitializationConfig::default()).unwrap();
+    if !options.emits.iter().any(|e| e.needs_llvm()) {
+        return;
+    }
 
-    let triple = TargetMachine::get_default_triple().to_string();
-    let target = Target::from_triple(&triple).unwrap();
-    let target_machine = target.create_target_machine(&triple, "generic", "", OptimizationLevel::None, RelocMode::Default, CodeModel::Default).unwrap();
+    let triple = match &options.target {
+        Some(triple) => {
+            Target::initialize_all(&InitializationConfig::default());
+            triple.clone()
+        },
+        None => {
+            Target::initialize_native(&InitializationConfig::default()).unwrap();
+            TargetMachine::get_default_triple().to_string()
+        },
+    };
+    let target = match Target::from_triple(&triple) {
+        Ok(target) => target,
+        Err(message) => panic!("Unknown target {}: {}", triple, message.to_string()),
+    };
+    let target_machine = target
+        .create_target_machine(&triple, &options.cpu, &options.features, options.opt_level.get_llvm_level(), RelocMode::Default, CodeModel::Default)
+        .unwrap_or_else(|| panic!("Unable to create the target machine for {} with the CPU {}", triple, options.cpu));
 
-    let output_path = Path::new("./output/main.o");
+    if let Err(message) = emit::check_target(&[file.deref()], &triple) {
+        panic!("{}", message);
+    }
 
-    println!("Write LLVM object to {}", output_path.display());
-    target_machine.write_to_file(&module, FileType::Object, &output_path).unwrap();
-}
+    let module = emit::apply_for_target(&[file.deref()], &triple, &target_machine.get_target_data(), options.is_debug);
+    optimize::apply(&module, options.opt_level);
 
+    for emit in options.emits.iter().filter(|e| e.needs_llvm()) {
+        let output_path = Path::new(get_output(options, *emit).unwrap());
+
+        match emit {
+            Emit::LlvmIr => module.print_to_file(output_path).expect("Unable to write the LLVM IR"),
+            Emit::Bitcode => if !module.write_bitcode_to_path(output_path) {
+                panic!("Unable to write the bitcode to {}", output_path.display());
+            },
+            Emit::Asm => target_machine.write_to_file(&module, FileType::Assembly, output_path).expect("Unable to write the assembly"),
+            Emit::Obj => target_machine.write_to_file(&module, FileType::Object, output_path).expect("Unable to write the object"),
+            Emit::Tokens | Emit::Ast | Emit::TypedAst => (),
+        }
+    }
+}
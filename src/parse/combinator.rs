@@ -226,6 +226,18 @@ pub fn identifier<'def: 'r, 'r>(input: Tokens<'def, 'r>) -> ParseResult<'def, 'r
     }
 }
 
+pub fn doc_comment<'def: 'r, 'r>(input: Tokens<'def, 'r>) -> ParseResult<'def, 'r, Span<'def>> {
+    if input.is_empty() {
+        return Err(input);
+    }
+
+    if let Token::DocComment(span) = &input[0] {
+        Ok((&input[1..], *span))
+    } else {
+        Err(input)
+    }
+}
+
 pub fn capitalize<'def: 'r, 'r>(input: Tokens<'def, 'r>) -> ParseResult<'def, 'r, Span<'def>> {
     if input.is_empty() {
         return Err(input);
@@ -263,7 +275,7 @@ pub fn get_and_not_followed_by<'def: 'r, 'r, I, F>(
 
         if input.len() > 0
             && result.line == input[0].span().line
-            && result.col + result.fragment.len() == input[0].span().col
+            && result.col + result.fragment.chars().count() == input[0].span().col
         {
             if let Ok((_, followed)) = followed(input) {
                 return Err(original);
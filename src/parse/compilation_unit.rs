@@ -1,4 +1,4 @@
-use parse::combinator::{many1, opt};
+use parse::combinator::{many0, many1, opt, doc_comment};
 use parse::def::{method, class};
 use parse::tree::{CompilationUnit, CompilationUnitItem};
 use parse::{ParseResult, Tokens};
@@ -7,12 +7,15 @@ use tokenize::token::Token;
 pub fn parse_item<'def, 'r>(
     input: Tokens<'def, 'r>,
 ) -> ParseResult<'def, 'r, CompilationUnitItem<'def>> {
+    let (input, doc_comments) = many0(doc_comment)(input)?;
 
     if let Ok((input, _)) = method::parse_prefix(input) {
-        let (input, method) = method::parse_tail(input)?;
+        let (input, mut method) = method::parse_tail(input)?;
+        method.doc_comments = doc_comments;
         Ok((input, CompilationUnitItem::Method(method)))
     } else if let Ok((input, _)) = class::parse_prefix(input) {
-        let (input, class) = class::parse_tail(input)?;
+        let (input, mut class) = class::parse_tail(input)?;
+        class.doc_comments = doc_comments;
         Ok((input, CompilationUnitItem::Class(class)))
     } else {
         Err(input)
@@ -1,8 +1,8 @@
 use parse::{Tokens, ParseResult, tpe, expr};
 use tokenize::span::Span;
-use parse::combinator::{keyword, identifier, symbol, many0, capitalize, separated_list, opt};
-use parse::tree::Class;
-use parse::def::{params, method};
+use parse::combinator::{keyword, identifier, symbol, many0, capitalize, separated_list, opt, doc_comment};
+use parse::tree::{Class, Method, Param};
+use parse::def::{params, method, field};
 use std::cell::Cell;
 
 pub fn parse_prefix<'def, 'r>(
@@ -17,19 +17,61 @@ pub fn parse_tail<'def, 'r>(
     let (input, name) = capitalize(input)?;
     let (input, params) = opt(params::parse)(input)?;
 
-    let (input, methods) = many0(method::parse)(input)?;
+    let (input, items) = many0(parse_item)(input)?;
 
     let (input, _) = keyword("end")(input)?;
 
+    let params = params.unwrap_or(vec![]);
+    let mut fields = vec![];
+    let mut methods = vec![];
+
+    for item in items {
+        match item {
+            ClassItem::Field(mut f) => {
+                f.index = params.len() + fields.len();
+                fields.push(f);
+            },
+            ClassItem::Method(m) => methods.push(m),
+        }
+    }
+
     return Ok((input, Class {
         name,
-        params: params.unwrap_or(vec![]),
+        params,
+        fields,
         methods,
+        doc_comments: vec![],
         llvm: Cell::new(None),
         llvm_native: Cell::new(None),
     }))
 }
 
+enum ClassItem<'def> {
+    Field(Param<'def>),
+    Method(Method<'def>),
+}
+
+fn parse_item<'def, 'r>(
+    input: Tokens<'def, 'r>,
+) -> ParseResult<'def, 'r, ClassItem<'def>> {
+    if let Ok((input, f)) = field::parse(input) {
+        return Ok((input, ClassItem::Field(f)));
+    }
+
+    let (input, doc_comments) = many0(doc_comment)(input)?;
+
+    if let Ok((input, _)) = keyword("static")(input) {
+        let (input, mut m) = method::parse(input)?;
+        m.is_static = true;
+        m.doc_comments = doc_comments;
+        return Ok((input, ClassItem::Method(m)));
+    }
+
+    let (input, mut m) = method::parse(input)?;
+    m.doc_comments = doc_comments;
+    Ok((input, ClassItem::Method(m)))
+}
+
 pub fn parse<'def, 'r>(
     input: Tokens<'def, 'r>,
 ) -> ParseResult<'def, 'r, Class<'def>> {
@@ -42,7 +84,7 @@ mod tests {
     use parse::Tokens;
     use parse::def::class;
     use test_common::{generate_tokens, span};
-    use parse::tree::{Method, Type, Expr, LiteralString, Class};
+    use parse::tree::{Method, Type, Expr, LiteralString, Class, Param};
     use std::cell::Cell;
 
     #[test]
@@ -61,16 +103,128 @@ end
                 Class {
                     name: span(1, 7, "Test"),
                     params: vec![],
+                    fields: vec![],
                     methods: vec![
                         Method {
                             name: span(2, 7, "test"),
                             params: vec![],
                             exprs: vec![],
                             return_type: Type { span: Some(span(2, 13, "Number")), class_def: None },
+                            is_static: false,
+                            parent_class: None,
+                            doc_comments: vec![],
+                            llvm: Cell::new(None)
+                        }
+                    ],
+                    doc_comments: vec![],
+                    llvm: Cell::new(None),
+                    llvm_native: Cell::new(None),
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_fields() {
+        assert_eq!(
+            class::parse(&generate_tokens(
+                r#"
+class Test(a: Number)
+  var b: Number
+  def test: Number
+  end
+  val c: Number
+end
+           "#
+            )),
+            Ok((
+                &[] as Tokens,
+                Class {
+                    name: span(1, 7, "Test"),
+                    params: vec![
+                        Param {
+                            name: Some(span(1, 12, "a")),
+                            tpe: Type { span: Some(span(1, 15, "Number")), class_def: None },
+                            is_varargs: false,
+                            is_mutable: false,
+                            value_opt: None,
+                            index: 0,
+                            parent: None,
+                            llvm: Cell::new(None),
+                        }
+                    ],
+                    fields: vec![
+                        Param {
+                            name: Some(span(2, 7, "b")),
+                            tpe: Type { span: Some(span(2, 10, "Number")), class_def: None },
+                            is_varargs: false,
+                            is_mutable: true,
+                            value_opt: None,
+                            index: 1,
+                            parent: None,
+                            llvm: Cell::new(None),
+                        },
+                        Param {
+                            name: Some(span(5, 7, "c")),
+                            tpe: Type { span: Some(span(5, 10, "Number")), class_def: None },
+                            is_varargs: false,
+                            is_mutable: false,
+                            value_opt: None,
+                            index: 2,
+                            parent: None,
+                            llvm: Cell::new(None),
+                        }
+                    ],
+                    methods: vec![
+                        Method {
+                            name: span(3, 7, "test"),
+                            params: vec![],
+                            exprs: vec![],
+                            return_type: Type { span: Some(span(3, 13, "Number")), class_def: None },
+                            is_static: false,
+                            parent_class: None,
+                            doc_comments: vec![],
+                            llvm: Cell::new(None)
+                        }
+                    ],
+                    doc_comments: vec![],
+                    llvm: Cell::new(None),
+                    llvm_native: Cell::new(None),
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_static() {
+        assert_eq!(
+            class::parse(&generate_tokens(
+                r#"
+class Test
+  static def test: Number
+  end
+end
+           "#
+            )),
+            Ok((
+                &[] as Tokens,
+                Class {
+                    name: span(1, 7, "Test"),
+                    params: vec![],
+                    fields: vec![],
+                    methods: vec![
+                        Method {
+                            name: span(2, 14, "test"),
+                            params: vec![],
+                            exprs: vec![],
+                            return_type: Type { span: Some(span(2, 20, "Number")), class_def: None },
+                            is_static: true,
                             parent_class: None,
+                            doc_comments: vec![],
                             llvm: Cell::new(None)
                         }
                     ],
+                    doc_comments: vec![],
                     llvm: Cell::new(None),
                     llvm_native: Cell::new(None),
                 }
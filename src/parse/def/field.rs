@@ -0,0 +1,94 @@
+use parse::{Tokens, ParseResult, tpe, expr};
+use parse::tree::{Param, Expr};
+use parse::combinator::{keyword, identifier, symbol, opt};
+use std::cell::Cell;
+
+pub fn parse<'def, 'r>(
+    input: Tokens<'def, 'r>,
+) -> ParseResult<'def, 'r, Param<'def>> {
+    let (input, is_mutable) = if let Ok((input, _)) = keyword("var")(input) {
+        (input, true)
+    } else {
+        let (input, _) = keyword("val")(input)?;
+        (input, false)
+    };
+    let (input, name) = identifier(input)?;
+    let (input, _) = symbol(':')(input)?;
+    let (input, tpe) = tpe::parse(input)?;
+    let (input, value_opt) = opt(parse_value)(input)?;
+
+    Ok((input, Param {
+        name: Some(name),
+        tpe,
+        is_varargs: false,
+        is_mutable,
+        value_opt,
+        index: 100000,
+        parent: None,
+        llvm: Cell::new(None),
+    }))
+}
+
+fn parse_value<'def, 'r>(
+    input: Tokens<'def, 'r>,
+) -> ParseResult<'def, 'r, Expr<'def>> {
+    let (input, _) = symbol('=')(input)?;
+    expr::parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use parse::Tokens;
+    use parse::def::field;
+    use test_common::{generate_tokens, span};
+    use parse::tree::{Type, Expr, Param, Int};
+    use std::cell::Cell;
+
+    #[test]
+    fn test_var() {
+        assert_eq!(
+            field::parse(&generate_tokens(
+                r#"
+var count: Int = 1
+           "#
+            )),
+            Ok((
+                &[] as Tokens,
+                Param {
+                    name: Some(span(1, 5, "count")),
+                    tpe: Type { span: Some(span(1, 12, "Int")), class_def: None },
+                    is_varargs: false,
+                    is_mutable: true,
+                    value_opt: Some(Expr::Int(Box::new(Int { span: span(1, 18, "1"), instance: None }))),
+                    index: 100000,
+                    parent: None,
+                    llvm: Cell::new(None),
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_val() {
+        assert_eq!(
+            field::parse(&generate_tokens(
+                r#"
+val name: String
+           "#
+            )),
+            Ok((
+                &[] as Tokens,
+                Param {
+                    name: Some(span(1, 5, "name")),
+                    tpe: Type { span: Some(span(1, 11, "String")), class_def: None },
+                    is_varargs: false,
+                    is_mutable: false,
+                    value_opt: None,
+                    index: 100000,
+                    parent: None,
+                    llvm: Cell::new(None),
+                }
+            ))
+        );
+    }
+}
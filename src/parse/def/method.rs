@@ -1,7 +1,7 @@
 use parse::{Tokens, ParseResult, tpe, expr};
 use parse::tree::Method;
 use tokenize::span::Span;
-use parse::combinator::{keyword, identifier, symbol, many0, separated_list, opt};
+use parse::combinator::{keyword, identifier, symbol, symbol2, many0, separated_list, opt};
 use parse::def::params;
 use std::cell::Cell;
 
@@ -14,7 +14,7 @@ pub fn parse_prefix<'def, 'r>(
 pub fn parse_tail<'def, 'r>(
     input: Tokens<'def, 'r>,
 ) -> ParseResult<'def, 'r, Method<'def>> {
-    let (input, name) = identifier(input)?;
+    let (input, name) = identifier(input).or_else(|_| symbol2('=', '=')(input))?;
     let (input, params) = opt(params::parse)(input)?;
     let (input, _) = symbol(':')(input)?;
     let (input, tpe) = tpe::parse(input)?;
@@ -28,7 +28,9 @@ pub fn parse_tail<'def, 'r>(
         params: params.unwrap_or(vec![]),
         exprs,
         return_type: tpe,
+        is_static: false,
         parent_class: None,
+        doc_comments: vec![],
         llvm: Cell::new(None)
     }))
 }
@@ -70,7 +72,9 @@ end
                        }))
                    ],
                    return_type: Type { span: Some(span(1, 13, "Number")), class_def: None },
+                   is_static: false,
                    parent_class: None,
+                   doc_comments: vec![],
                    llvm: Cell::new(None)
                }
            ))
@@ -95,6 +99,8 @@ end
                             name: Some(span(1, 10, "a")),
                             tpe: Type { span: Some(span(1, 13, "String")), class_def: None },
                             is_varargs: false,
+                            is_mutable: false,
+                            value_opt: None,
                             index: 0,
                             parent: None,
                             llvm: Cell::new(None),
@@ -103,6 +109,8 @@ end
                             name: Some(span(1, 21, "b")),
                             tpe: Type { span: Some(span(1, 27, "String")), class_def: None },
                             is_varargs: true,
+                            is_mutable: false,
+                            value_opt: None,
                             index: 1,
                             parent: None,
                             llvm: Cell::new(None),
@@ -110,7 +118,9 @@ end
                     ],
                     exprs: vec![],
                     return_type: Type { span: Some(span(1, 36, "Number")), class_def: None },
+                    is_static: false,
                     parent_class: None,
+                    doc_comments: vec![],
                     llvm: Cell::new(None)
                 }
             ))
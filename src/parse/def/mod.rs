@@ -1,3 +1,4 @@
 pub mod class;
+pub mod field;
 pub mod method;
-pub mod params;
\ No newline at end of file
+pub mod params;
@@ -30,6 +30,8 @@ fn parse_single<'def, 'r>(
         name: Some(name),
         tpe,
         is_varargs: varargs_opt.is_some(),
+        is_mutable: false,
+        value_opt: None,
         index: 100000,
         parent: None,
         llvm: Cell::new(None),
@@ -66,6 +68,8 @@ mod tests {
                         name: Some(span(1, 2, "arg")),
                         tpe: Type { span: Some(span(1, 7, "Number")), class_def: None },
                         is_varargs: false,
+                        is_mutable: false,
+                        value_opt: None,
                         index: 0,
                         parent: None,
                         llvm: Cell::new(None),
@@ -74,6 +78,8 @@ mod tests {
                         name: Some(span(1, 15, "arg2")),
                         tpe: Type { span: Some(span(1, 24, "Number")), class_def: None },
                         is_varargs: true,
+                        is_mutable: false,
+                        value_opt: None,
                         index: 1,
                         parent: None,
                         llvm: Cell::new(None),
@@ -0,0 +1,10 @@
+use parse::{Tokens, ParseResult};
+use parse::tree::Boolean;
+use parse::combinator::keyword;
+
+pub fn parse<'def, 'r>(
+    input: Tokens<'def, 'r>,
+) -> ParseResult<'def, 'r, Boolean<'def>> {
+    let (input, span) = keyword("true")(input).or_else(|_| keyword("false")(input))?;
+    Ok((input, Boolean { span, instance: None }))
+}
@@ -0,0 +1,13 @@
+use parse::tree::Float;
+use parse::{Tokens, ParseResult};
+use tokenize::token::Token;
+
+pub fn parse<'def, 'r>(
+    input: Tokens<'def, 'r>,
+) -> ParseResult<'def, 'r, Float<'def>> {
+    if let Some(Token::Float(span)) = input.first() {
+        Ok((&input[1..], Float { span: *span, instance: None }))
+    } else {
+        Err(input)
+    }
+}
@@ -14,7 +14,7 @@ pub fn parse<'def, 'r>(
 pub fn parse_span<'def, 'r>(
     input: Tokens<'def, 'r>,
 ) -> ParseResult<'def, 'r, Span<'def>> {
-    if let Token::Identifier(name) = input[0] {
+    if let Some(&Token::Identifier(name)) = input.first() {
         Ok((&input[1..], name))
     } else {
         Err(input)
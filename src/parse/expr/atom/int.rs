@@ -6,7 +6,7 @@ use std::cell::RefCell;
 pub fn parse<'def, 'r>(
     input: Tokens<'def, 'r>,
 ) -> ParseResult<'def, 'r, Int<'def>> {
-    if let Token::Int(span) = &input[0] {
+    if let Some(Token::Int(span)) = input.first() {
         Ok((&input[1..], Int { span: *span, instance: None }))
     } else {
         Err(input)
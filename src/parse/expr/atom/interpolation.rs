@@ -0,0 +1,73 @@
+use parse::{Tokens, ParseResult, expr};
+use parse::tree::{Interpolation, InterpolationPart, LiteralString};
+use tokenize;
+use tokenize::StringPart;
+use tokenize::token::Token;
+
+pub fn parse<'def, 'r>(
+    input: Tokens<'def, 'r>,
+) -> ParseResult<'def, 'r, Interpolation<'def>> {
+    if input.is_empty() {
+        return Err(input);
+    }
+
+    let span = if let Some(&Token::Interpolation(span)) = input.first() {
+        span
+    } else {
+        return Err(input);
+    };
+
+    let mut parts = vec![];
+
+    for part in tokenize::split_interpolation(span).map_err(|_| input)? {
+        parts.push(
+            match part {
+                StringPart::Text(text) => InterpolationPart::Text(LiteralString { span: text, instance: None }),
+                StringPart::Expr(expr_span) => {
+                    let tokens = tokenize::apply_span(expr_span).map_err(|_| input)?;
+                    match expr::parse(&tokens) {
+                        Ok((rest, e)) if rest.is_empty() => InterpolationPart::Expr(e),
+                        _ => return Err(input),
+                    }
+                },
+            }
+        );
+    }
+
+    Ok((&input[1..], Interpolation { span, parts, class_def: None }))
+}
+
+#[cfg(test)]
+mod tests {
+    use parse::Tokens;
+    use parse::expr::atom::interpolation;
+    use parse::tree::{Interpolation, InterpolationPart, LiteralString, Expr, Identifier, MemberAccess};
+    use test_common::{generate_tokens, span};
+
+    #[test]
+    fn test_simple() {
+        assert_eq!(
+            interpolation::parse(&generate_tokens(
+                r#"
+"Hello #{name.first}!"
+           "#
+            )),
+            Ok((
+                &[] as Tokens,
+                Interpolation {
+                    span: span(1, 1, "\"Hello #{name.first}!\""),
+                    parts: vec![
+                        InterpolationPart::Text(LiteralString { span: span(1, 2, "Hello "), instance: None }),
+                        InterpolationPart::Expr(Expr::MemberAccess(Box::new(MemberAccess {
+                            parent: Expr::Identifier(Box::new(Identifier { name: Some(span(1, 10, "name")), source: None })),
+                            name: Some(span(1, 15, "first")),
+                            param_def: None,
+                        }))),
+                        InterpolationPart::Text(LiteralString { span: span(1, 21, "!"), instance: None }),
+                    ],
+                    class_def: None,
+                }
+            ))
+        );
+    }
+}
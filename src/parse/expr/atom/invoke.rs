@@ -30,6 +30,7 @@ pub fn parse_tail<'def, 'r>(
                 name,
                 args,
                 method_def: None,
+                static_invoker_opt: None,
             }
         ))
     } else {
@@ -72,6 +73,7 @@ func("a", "b")
                         Expr::String(Box::new(LiteralString { span: span(1, 11, "\"b\""), instance: None })),
                     ],
                     method_def: None,
+                    static_invoker_opt: None,
                 }
             ))
         );
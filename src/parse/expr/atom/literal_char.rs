@@ -6,7 +6,7 @@ use std::cell::RefCell;
 pub fn parse<'def, 'r>(
     input: Tokens<'def, 'r>,
 ) -> ParseResult<'def, 'r, Char<'def>> {
-    if let Token::Char(span) = &input[0] {
+    if let Some(Token::Char(span)) = input.first() {
         Ok((&input[1..], Char { span: *span, instance: None }))
     } else {
         Err(input)
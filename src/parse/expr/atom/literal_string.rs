@@ -6,7 +6,7 @@ use std::cell::RefCell;
 pub fn parse<'def, 'r>(
     input: Tokens<'def, 'r>,
 ) -> ParseResult<'def, 'r, LiteralString<'def>> {
-    if let Token::String(span) = &input[0] {
+    if let Some(Token::String(span)) = input.first() {
         Ok((&input[1..], LiteralString { span: *span, instance: None }))
     } else {
         Err(input)
@@ -1,24 +1,36 @@
 use parse::{Tokens, ParseResult};
 use parse::tree::Expr;
 
+pub mod boolean;
+pub mod float;
 pub mod identifier;
 pub mod int;
+pub mod interpolation;
 pub mod invoke;
 pub mod literal_string;
 pub mod literal_char;
 pub mod new_instance;
+pub mod self_instance;
 
 pub fn parse<'def, 'r>(
     input: Tokens<'def, 'r>,
 ) -> ParseResult<'def, 'r, Expr<'def>> {
     if let Ok((input, i)) = literal_string::parse(input) {
         Ok((input, Expr::String(Box::new(i))))
+    } else if let Ok((input, i)) = interpolation::parse(input) {
+        Ok((input, Expr::Interpolation(Box::new(i))))
     } else if let Ok((input, i)) = literal_char::parse(input) {
         Ok((input, Expr::Char(Box::new(i))))
     } else if let Ok((input, i)) = invoke::parse(input) {
         Ok((input, Expr::Invoke(Box::new(i))))
     } else if let Ok((input, i)) = new_instance::parse(input) {
         Ok((input, Expr::NewInstance(Box::new(i))))
+    } else if let Ok((input, i)) = boolean::parse(input) {
+        Ok((input, Expr::Boolean(Box::new(i))))
+    } else if let Ok((input, i)) = self_instance::parse(input) {
+        Ok((input, Expr::SelfInstance(Box::new(i))))
+    } else if let Ok((input, i)) = float::parse(input) {
+        Ok((input, Expr::Float(Box::new(i))))
     } else if let Ok((input, i)) = int::parse(input) {
         Ok((input, Expr::Int(Box::new(i))))
     } else if let Ok((input, i)) = identifier::parse(input) {
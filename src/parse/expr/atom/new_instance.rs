@@ -17,6 +17,7 @@ pub fn parse<'def, 'r>(
                 name_opt: Some(name),
                 args,
                 class_def: None,
+                constructor_def: None,
             }
         ))
     } else {
@@ -27,7 +28,7 @@ pub fn parse<'def, 'r>(
 fn parse_capitalize<'def, 'r>(
     input: Tokens<'def, 'r>,
 ) -> ParseResult<'def, 'r, Span<'def>> {
-    if let Token::Capitalize(name) = input[0] {
+    if let Some(&Token::Capitalize(name)) = input.first() {
         Ok((&input[1..], name))
     } else {
         Err(input)
@@ -59,6 +60,7 @@ Int("a", 5)
                         Expr::Int(Box::new(Int { span: span(1, 10, "5"), instance: None })),
                     ],
                     class_def: None,
+                    constructor_def: None,
                 }
             ))
         );
@@ -0,0 +1,33 @@
+use parse::{Tokens, ParseResult};
+use parse::tree::SelfInstance;
+use parse::combinator::keyword;
+
+pub fn parse<'def, 'r>(
+    input: Tokens<'def, 'r>,
+) -> ParseResult<'def, 'r, SelfInstance<'def>> {
+    let (input, span) = keyword("self")(input)?;
+    Ok((input, SelfInstance { span: Some(span), param_def: None }))
+}
+
+#[cfg(test)]
+mod tests {
+    use parse::Tokens;
+    use parse::expr::atom::self_instance;
+    use parse::tree::SelfInstance;
+    use test_common::{generate_tokens, span};
+
+    #[test]
+    fn test_simple() {
+        assert_eq!(
+            self_instance::parse(&generate_tokens(
+                r#"
+self
+           "#
+            )),
+            Ok((
+                &[] as Tokens,
+                SelfInstance { span: Some(span(1, 1, "self")), param_def: None }
+            ))
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use parse::{Tokens, ParseResult, expr};
 use parse::tree::{Expr, Invoke, MemberAccess};
 use parse::expr::atom;
-use parse::combinator::{symbol, separated_list};
+use parse::combinator::{symbol, separated_list, capitalize};
 use tokenize::token::Token;
 use tokenize::span::Span;
 use parse::expr::atom::{invoke, identifier};
@@ -10,11 +10,27 @@ use std::cell::Cell;
 pub fn parse<'def, 'r>(
     input: Tokens<'def, 'r>,
 ) -> ParseResult<'def, 'r, Expr<'def>> {
+    if let Ok((input, invoke)) = parse_static_invoke(input) {
+        return parse_tail(Expr::Invoke(Box::new(invoke)), input);
+    }
+
     let (input, left) = atom::parse(input)?;
 
     parse_tail(left, input)
 }
 
+fn parse_static_invoke<'def, 'r>(
+    input: Tokens<'def, 'r>,
+) -> ParseResult<'def, 'r, Invoke<'def>> {
+    let (input, class_name) = capitalize(input)?;
+    let (input, _) = symbol('.')(input)?;
+    let (input, name) = identifier::parse_span(input)?;
+    let (input, mut invoke) = invoke::parse_tail(name, input)?;
+
+    invoke.static_invoker_opt = Some(class_name);
+    Ok((input, invoke))
+}
+
 fn parse_tail<'def, 'r>(
     left: Expr<'def>,
     input: Tokens<'def, 'r>,
@@ -31,6 +47,7 @@ fn parse_tail<'def, 'r>(
                         name: invoke.name,
                         args: invoke.args,
                         method_def: None,
+                        static_invoker_opt: None,
                     }
                 ))
             )
@@ -77,6 +94,7 @@ func("a").member.another_func()
                                 name: span(1, 1, "func"),
                                 args: vec![Expr::String(Box::new(LiteralString { span: span(1, 6, "\"a\""), instance: None }))],
                                 method_def: None,
+                                static_invoker_opt: None,
                             })),
                             name: Some(span(1, 11, "member")),
                             param_def: None
@@ -84,9 +102,35 @@ func("a").member.another_func()
                         name: span(1, 18, "another_func"),
                         args: vec![],
                         method_def: None,
+                        static_invoker_opt: None,
                     }
                 ))
             ))
         );
     }
+
+    #[test]
+    fn test_static_invoke() {
+        assert_eq!(
+            level_010::parse(&generate_tokens(
+                r#"
+Config.parse("a").name
+           "#
+            )),
+            Ok((
+                &[] as Tokens,
+                Expr::MemberAccess(Box::new(MemberAccess {
+                    parent: Expr::Invoke(Box::new(Invoke {
+                        invoker_opt: None,
+                        name: span(1, 8, "parse"),
+                        args: vec![Expr::String(Box::new(LiteralString { span: span(1, 14, "\"a\""), instance: None }))],
+                        method_def: None,
+                        static_invoker_opt: Some(span(1, 1, "Config")),
+                    })),
+                    name: Some(span(1, 19, "name")),
+                    param_def: None
+                }))
+            ))
+        );
+    }
 }
@@ -0,0 +1,64 @@
+use parse::{Tokens, ParseResult};
+use parse::tree::{Expr, Invoke};
+use parse::combinator::symbol2;
+use parse::expr::level_010;
+
+// `a == b` is sugar for `a.==(b)`, so any class can define the method `==`.
+pub fn parse<'def, 'r>(
+    input: Tokens<'def, 'r>,
+) -> ParseResult<'def, 'r, Expr<'def>> {
+    let (input, left) = level_010::parse(input)?;
+
+    parse_tail(left, input)
+}
+
+fn parse_tail<'def, 'r>(
+    left: Expr<'def>,
+    input: Tokens<'def, 'r>,
+) -> ParseResult<'def, 'r, Expr<'def>> {
+    if let Ok((input, operator)) = symbol2('=', '=')(input) {
+        let (input, right) = level_010::parse(input)?;
+
+        return parse_tail(
+            Expr::Invoke(Box::new(Invoke {
+                invoker_opt: Some(left),
+                name: operator,
+                args: vec![right],
+                method_def: None,
+                static_invoker_opt: None,
+            })),
+            input
+        );
+    }
+
+    Ok((input, left))
+}
+
+#[cfg(test)]
+mod tests {
+    use parse::Tokens;
+    use test_common::{generate_tokens, span};
+    use parse::expr::level_012;
+    use parse::tree::{Expr, Invoke, Identifier, Int};
+
+    #[test]
+    fn test_equal() {
+        assert_eq!(
+            level_012::parse(&generate_tokens(
+                r#"
+a == 1
+           "#
+            )),
+            Ok((
+                &[] as Tokens,
+                Expr::Invoke(Box::new(Invoke {
+                    invoker_opt: Some(Expr::Identifier(Box::new(Identifier { name: Some(span(1, 1, "a")), source: None }))),
+                    name: span(1, 3, "=="),
+                    args: vec![Expr::Int(Box::new(Int { span: span(1, 6, "1"), instance: None }))],
+                    method_def: None,
+                    static_invoker_opt: None,
+                }))
+            ))
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use parse::{Tokens, ParseResult};
+use parse::tree::{Expr, LogicalOperation};
+use parse::combinator::symbol2;
+use parse::expr::level_012;
+
+pub fn parse<'def, 'r>(
+    input: Tokens<'def, 'r>,
+) -> ParseResult<'def, 'r, Expr<'def>> {
+    let (input, left) = level_012::parse(input)?;
+
+    parse_tail(left, input)
+}
+
+fn parse_tail<'def, 'r>(
+    left: Expr<'def>,
+    input: Tokens<'def, 'r>,
+) -> ParseResult<'def, 'r, Expr<'def>> {
+    if let Ok((input, operator)) = symbol2('&', '&')(input) {
+        let (input, right) = level_012::parse(input)?;
+
+        return parse_tail(
+            Expr::LogicalOperation(Box::new(LogicalOperation { left, operator, right, class_def: None })),
+            input
+        );
+    }
+
+    Ok((input, left))
+}
@@ -0,0 +1,65 @@
+use parse::{Tokens, ParseResult};
+use parse::tree::{Expr, LogicalOperation};
+use parse::combinator::symbol2;
+use parse::expr::level_013;
+
+pub fn parse<'def, 'r>(
+    input: Tokens<'def, 'r>,
+) -> ParseResult<'def, 'r, Expr<'def>> {
+    let (input, left) = level_013::parse(input)?;
+
+    parse_tail(left, input)
+}
+
+fn parse_tail<'def, 'r>(
+    left: Expr<'def>,
+    input: Tokens<'def, 'r>,
+) -> ParseResult<'def, 'r, Expr<'def>> {
+    if let Ok((input, operator)) = symbol2('|', '|')(input) {
+        let (input, right) = level_013::parse(input)?;
+
+        return parse_tail(
+            Expr::LogicalOperation(Box::new(LogicalOperation { left, operator, right, class_def: None })),
+            input
+        );
+    }
+
+    Ok((input, left))
+}
+
+#[cfg(test)]
+mod tests {
+    use parse::Tokens;
+    use test_common::{generate_tokens, span};
+    use parse::expr::level_014;
+    use parse::tree::{Expr, LogicalOperation, Identifier};
+
+    fn identifier(col: usize, name: &str) -> Expr {
+        Expr::Identifier(Box::new(Identifier { name: Some(span(1, col, name)), source: None }))
+    }
+
+    #[test]
+    fn test_precedence() {
+        assert_eq!(
+            level_014::parse(&generate_tokens(
+                r#"
+a || b && c
+           "#
+            )),
+            Ok((
+                &[] as Tokens,
+                Expr::LogicalOperation(Box::new(LogicalOperation {
+                    left: identifier(1, "a"),
+                    operator: span(1, 3, "||"),
+                    right: Expr::LogicalOperation(Box::new(LogicalOperation {
+                        left: identifier(6, "b"),
+                        operator: span(1, 8, "&&"),
+                        right: identifier(11, "c"),
+                        class_def: None,
+                    })),
+                    class_def: None,
+                }))
+            ))
+        );
+    }
+}
@@ -1,34 +1,160 @@
 use parse::{Tokens, ParseResult, expr};
-use parse::tree::{Expr, Assignment};
+use parse::tree::{Expr, Assignment, MemberAccess, MemberAssignment, Using};
 use parse::expr::atom::identifier;
-use parse::combinator::symbol;
+use parse::combinator::{symbol, keyword, many0};
 use std::cell::Cell;
-use parse::expr::level_010;
+use parse::expr::level_014;
 
 pub fn parse<'def, 'r>(
     input: Tokens<'def, 'r>,
 ) -> ParseResult<'def, 'r, Expr<'def>> {
+    if let Ok((input, e)) = parse_using(input) {
+        return Ok((input, e));
+    }
+
     if let Ok((input, e)) = parse_assignment(input) {
-        Ok((input, e))
+        return Ok((input, e));
+    }
+
+    let (input, left) = level_014::parse(input)?;
+
+    match left {
+        Expr::MemberAccess(member) => parse_member_assignment(*member, input),
+        other => Ok((input, other)),
+    }
+}
+
+fn parse_member_assignment<'def, 'r>(
+    member: MemberAccess<'def>,
+    input: Tokens<'def, 'r>,
+) -> ParseResult<'def, 'r, Expr<'def>> {
+    if let Ok((input, _)) = symbol('=')(input) {
+        let (input, expr) = expr::parse(input)?;
+
+        Ok((
+            input,
+            Expr::MemberAssignment(Box::new(MemberAssignment {
+                member,
+                expr: Box::new(expr),
+            }))
+        ))
     } else {
-        level_010::parse(input)
+        Ok((input, Expr::MemberAccess(Box::new(member))))
     }
 }
 
+fn parse_using<'def, 'r>(
+    input: Tokens<'def, 'r>,
+) -> ParseResult<'def, 'r, Expr<'def>> {
+    let (input, span) = keyword("using")(input)?;
+    let (input, assignment) = parse_assignment_tail(input)?;
+    let (input, exprs) = many0(expr::parse)(input)?;
+    let (input, _) = keyword("end")(input)?;
+
+    Ok((
+        input,
+        Expr::Using(Box::new(Using {
+            span,
+            assignment,
+            exprs,
+            class_def: None,
+        }))
+    ))
+}
+
 fn parse_assignment<'def, 'r>(
     input: Tokens<'def, 'r>,
 ) -> ParseResult<'def, 'r, Expr<'def>> {
+    let (input, assignment) = parse_assignment_tail(input)?;
+    Ok((input, Expr::Assignment(Box::from(assignment))))
+}
+
+fn parse_assignment_tail<'def, 'r>(
+    input: Tokens<'def, 'r>,
+) -> ParseResult<'def, 'r, Assignment<'def>> {
     let (input, name) = identifier::parse_span(input)?;
     let (input, _) = symbol('=')(input)?;
     let (input, expr) = expr::parse(input)?;
 
     Ok((
         input,
-        Expr::Assignment(Box::from(Assignment {
+        Assignment {
             name,
             expr: Box::new(expr),
             tpe: None,
             llvm: Cell::new(None),
-        }))
+        }
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use parse::Tokens;
+    use test_common::{generate_tokens, span};
+    use parse::expr::level_016;
+    use parse::tree::{Expr, MemberAccess, MemberAssignment, Identifier, Int, Using, Assignment, Invoke};
+    use std::cell::Cell;
+
+    #[test]
+    fn test_member_assignment() {
+        assert_eq!(
+            level_016::parse(&generate_tokens(
+                r#"
+counter.count = 1
+           "#
+            )),
+            Ok((
+                &[] as Tokens,
+                Expr::MemberAssignment(Box::new(MemberAssignment {
+                    member: MemberAccess {
+                        parent: Expr::Identifier(Box::new(Identifier { name: Some(span(1, 1, "counter")), source: None })),
+                        name: Some(span(1, 9, "count")),
+                        param_def: None
+                    },
+                    expr: Box::new(Expr::Int(Box::new(Int { span: span(1, 17, "1"), instance: None }))),
+                }))
+            ))
+        );
+    }
+
+    #[test]
+    fn test_using() {
+        assert_eq!(
+            level_016::parse(&generate_tokens(
+                r#"
+using pipe = open()
+  pipe.read()
+end
+           "#
+            )),
+            Ok((
+                &[] as Tokens,
+                Expr::Using(Box::new(Using {
+                    span: span(1, 1, "using"),
+                    assignment: Assignment {
+                        name: span(1, 7, "pipe"),
+                        expr: Box::new(Expr::Invoke(Box::new(Invoke {
+                            invoker_opt: None,
+                            static_invoker_opt: None,
+                            name: span(1, 14, "open"),
+                            args: vec![],
+                            method_def: None,
+                        }))),
+                        tpe: None,
+                        llvm: Cell::new(None),
+                    },
+                    exprs: vec![
+                        Expr::Invoke(Box::new(Invoke {
+                            invoker_opt: Some(Expr::Identifier(Box::new(Identifier { name: Some(span(2, 3, "pipe")), source: None }))),
+                            static_invoker_opt: None,
+                            name: span(2, 8, "read"),
+                            args: vec![],
+                            method_def: None,
+                        })),
+                    ],
+                    class_def: None,
+                }))
+            ))
+        );
+    }
+}
@@ -4,6 +4,9 @@ use parse::tree::Expr;
 
 pub mod atom;
 pub mod level_010;
+pub mod level_012;
+pub mod level_013;
+pub mod level_014;
 pub mod level_016;
 
 pub fn parse<'def, 'r>(
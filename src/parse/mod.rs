@@ -48,11 +48,16 @@ pub fn apply<'def, 'input, 'path>(
         Ok(tokens) => tokens,
         Err(span) => return Err(span),
     };
+    let tokens = strip_detached_doc_comments(tokens);
     let unit = match apply_tokens(
         unsafe { &*(&tokens as *const Vec<Token<'def>>) },
     ) {
         Ok(unit) => unit,
-        Err(tokens) => return Err(tokens.first().unwrap().span()),
+        // The tokens are empty when the file has no tokens at all, e.g. an empty file.
+        Err(tokens) => return Err(match tokens.first() {
+            Some(token) => token.span(),
+            None => Span { line: 1, col: 1, fragment: "", file: &*file },
+        }),
     };
 
     file.unit = unit;
@@ -60,6 +65,29 @@ pub fn apply<'def, 'input, 'path>(
     Ok(file)
 }
 
+// Doc comments are only attached to classes and methods. The others, e.g. the ones above a field, are ignored.
+fn strip_detached_doc_comments(tokens: Vec<Token>) -> Vec<Token> {
+    let mut result = Vec::with_capacity(tokens.len());
+    // The doc comments since the last other token, which are kept or dropped depending on the token after them.
+    let mut pending = vec![];
+
+    for token in tokens {
+        match token {
+            Token::DocComment(_) => pending.push(token),
+            Token::Keyword(k) if k.fragment == "class" || k.fragment == "def" || k.fragment == "static" => {
+                result.append(&mut pending);
+                result.push(token);
+            },
+            _ => {
+                pending.clear();
+                result.push(token);
+            },
+        }
+    }
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use std::ops::Deref;
@@ -90,16 +118,20 @@ end
                             Class {
                                 name: span2(1, 7, "Test", file.deref()),
                                 params: vec![],
+                                fields: vec![],
                                 methods: vec![
                                     Method {
                                         name: span2(2, 7, "test", file.deref()),
                                         params: vec![],
                                         exprs: vec![],
                                         return_type: Type { span: Some(span2(2, 15, "Number", file.deref())), class_def: None },
+                                        is_static: false,
                                         parent_class: None,
+                                        doc_comments: vec![],
                                         llvm: Cell::new(None)
                                     }
                                 ],
+                                doc_comments: vec![],
                                 llvm: Cell::new(None),
                                 llvm_native: Cell::new(None)
                             }
@@ -111,4 +143,28 @@ end
             }))
         );
     }
+
+    #[test]
+    fn test_doc_comments() {
+        let content = r#"
+/// A counter.
+///
+/// It only goes up.
+class Counter
+  /// Ignored because fields have no docs.
+  var count: Int = 0
+
+  /// Increments the count.
+  def inc(): Int
+    /// Ignored inside a body.
+    count
+  end
+end
+                           "#.trim();
+        let file = unwrap!(Ok, apply(content, ""));
+        let class = unwrap!(CompilationUnitItem::Class, file.unit.items.get(0).unwrap());
+
+        assert_eq!(class.get_doc(), "A counter.\n\nIt only goes up.");
+        assert_eq!(class.methods.get(0).unwrap().get_doc(), "Increments the count.");
+    }
 }
@@ -1,8 +1,7 @@
 use tokenize::span::Span;
 use std::cell::{Cell, RefCell};
-use inkwell::types::{StructType, BasicTypeEnum};
+use inkwell::types::StructType;
 use inkwell::values::{FunctionValue, PointerValue};
-use inkwell::AddressSpace;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct CompilationUnit<'a> {
@@ -19,20 +18,27 @@ pub enum CompilationUnitItem<'a> {
 pub struct Class<'a> {
     pub name: Span<'a>,
     pub params: Vec<Param<'a>>,
+    pub fields: Vec<Param<'a>>,
     pub methods: Vec<Method<'a>>,
+    pub doc_comments: Vec<Span<'a>>,
     pub llvm: Cell<Option<StructType>>,
     pub llvm_native: Cell<Option<StructType>>
 }
 
 impl <'a> Class<'a> {
+    pub fn get_doc(&self) -> String {
+        join_doc_comments(&self.doc_comments)
+    }
+
     pub fn find_method(&self, name: &str) -> &Method<'a> {
-        for method in &self.methods {
-           if method.name.fragment == name {
-               return method;
-           }
+        match self.find_method_opt(name) {
+            Some(method) => method,
+            None => panic!("Unable to find the method {} in the class {}", name, self.name.fragment),
         }
+    }
 
-        panic!("Unable to find the method {} in the class {}", name, self.name.fragment)
+    pub fn find_method_opt(&self, name: &str) -> Option<&Method<'a>> {
+        self.methods.iter().find(|m| m.name.fragment == name)
     }
 
     pub fn find_param(&self, name: &str) -> &Param<'a> {
@@ -44,6 +50,41 @@ impl <'a> Class<'a> {
 
         panic!("Unable to find the param {} in the class {}", name, self.name.fragment)
     }
+
+    pub fn find_field(&self, name: &str) -> &Param<'a> {
+        for field in &self.fields {
+            if field.name.unwrap().fragment == name {
+                return field;
+            }
+        }
+
+        panic!("Unable to find the field {} in the class {}", name, self.name.fragment)
+    }
+
+    // The members are laid out in the struct with the class params first, followed by the fields.
+    pub fn get_members(&self) -> Vec<&Param<'a>> {
+        self.params.iter().chain(self.fields.iter()).collect()
+    }
+
+    // A secondary constructor is an `init` method that returns an instance of its class.
+    pub fn is_constructor(&self, method: &Method<'a>) -> bool {
+        method.name.fragment == "init" && method.return_type.span.map(|s| s.fragment) == Some(self.name.fragment)
+    }
+
+    pub fn find_constructors(&self) -> Vec<&Method<'a>> {
+        self.methods.iter().filter(|m| self.is_constructor(m)).collect()
+    }
+
+    // The initializer is an `init` method that runs after the primary constructor populates the class params.
+    pub fn find_initializer(&self) -> Option<&Method<'a>> {
+        self.methods.iter().find(|m| m.name.fragment == "init" && !self.is_constructor(m))
+    }
+
+    // The finalizer is a `finalize` method that releases what the instance holds, e.g. a file descriptor.
+    // It runs when the instance is collected or at the end of its `using` block, whichever comes first.
+    pub fn find_finalizer(&self) -> Option<&Method<'a>> {
+        self.methods.iter().find(|m| m.name.fragment == "finalize" && !m.is_static)
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -52,31 +93,42 @@ pub struct Method<'a> {
     pub params: Vec<Param<'a>>,
     pub exprs: Vec<Expr<'a>>,
     pub return_type: Type<'a>,
+    pub is_static: bool,
     pub parent_class: Option<*const Class<'a>>,
+    pub doc_comments: Vec<Span<'a>>,
     pub llvm: Cell<Option<FunctionValue>>
 }
 
+impl <'a> Method<'a> {
+    pub fn get_doc(&self) -> String {
+        join_doc_comments(&self.doc_comments)
+    }
+}
+
+// Joins the `///` lines into the text of the doc, e.g. `/// Returns x` becomes `Returns x`.
+fn join_doc_comments(doc_comments: &[Span]) -> String {
+    doc_comments
+        .iter()
+        .map(|c| {
+            let line = &c.fragment["///".len()..];
+            if line.starts_with(' ') { &line[1..] } else { line }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Param<'a> {
     pub name: Option<Span<'a>>,
     pub tpe: Type<'a>,
     pub is_varargs: bool,
+    pub is_mutable: bool,
+    pub value_opt: Option<Expr<'a>>,
     pub index: usize,
     pub parent: Option<ParamParent<'a>>,
     pub llvm: Cell<Option<PointerValue>>,
 }
 
-impl <'a> Param<'a> {
-    pub fn get_llvm_type(&self) -> BasicTypeEnum {
-        let param_class = unsafe { &*self.tpe.class_def.unwrap() };
-        BasicTypeEnum::PointerType(if self.is_varargs {
-            param_class.llvm.get().unwrap().ptr_type(AddressSpace::Generic).ptr_type(AddressSpace::Generic)
-        } else {
-            param_class.llvm.get().unwrap().ptr_type(AddressSpace::Generic)
-        })
-    }
-}
-
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum ParamParent<'a> {
     Class(*const Class<'a>),
@@ -92,16 +144,49 @@ pub struct Type<'a> {
 #[derive(Debug, PartialEq, Clone)]
 pub enum Expr<'a> {
     Assignment(Box<Assignment<'a>>),
+    Boolean(Box<Boolean<'a>>),
     Char(Box<Char<'a>>),
+    Float(Box<Float<'a>>),
     Identifier(Box<Identifier<'a>>),
     Int(Box<Int<'a>>),
+    Interpolation(Box<Interpolation<'a>>),
     Invoke(Box<Invoke<'a>>),
+    LogicalOperation(Box<LogicalOperation<'a>>),
     MemberAccess(Box<MemberAccess<'a>>),
+    MemberAssignment(Box<MemberAssignment<'a>>),
+    NativeBool(Box<NativeBool>),
     NativeChar(Box<NativeChar>),
+    NativeFloat(Box<NativeFloat>),
     NativeInt(Box<NativeInt>),
     NativeString(Box<NativeString>),
     NewInstance(Box<NewInstance<'a>>),
+    SelfInstance(Box<SelfInstance<'a>>),
     String(Box<LiteralString<'a>>),
+    Using(Box<Using<'a>>),
+}
+
+impl <'a> Expr<'a> {
+    // The span that the debug info locates the expr at. The natives are built by the compiler, so they have none.
+    pub fn get_span(&self) -> Option<Span<'a>> {
+        match self {
+            Expr::Assignment(e) => Some(e.name),
+            Expr::Boolean(e) => Some(e.span),
+            Expr::Char(e) => Some(e.span),
+            Expr::Float(e) => Some(e.span),
+            Expr::Identifier(e) => e.name,
+            Expr::Int(e) => Some(e.span),
+            Expr::Interpolation(e) => Some(e.span),
+            Expr::Invoke(e) => Some(e.name),
+            Expr::LogicalOperation(e) => Some(e.operator),
+            Expr::MemberAccess(e) => e.name,
+            Expr::MemberAssignment(e) => e.member.name,
+            Expr::NewInstance(e) => e.name_opt,
+            Expr::SelfInstance(e) => e.span,
+            Expr::String(e) => Some(e.span),
+            Expr::Using(e) => Some(e.span),
+            Expr::NativeBool(_) | Expr::NativeChar(_) | Expr::NativeFloat(_) | Expr::NativeInt(_) | Expr::NativeString(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -112,6 +197,15 @@ pub struct Assignment<'a> {
     pub llvm: Cell<Option<PointerValue>>,
 }
 
+// `using name = expr ... end` finalizes the instance at the end of the block instead of when it's collected.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Using<'a> {
+    pub span: Span<'a>, // The `using` keyword
+    pub assignment: Assignment<'a>,
+    pub exprs: Vec<Expr<'a>>,
+    pub class_def: Option<*const Class<'a>>, // The type of the last expr, or Void
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Identifier<'a> {
     pub name: Option<Span<'a>>,
@@ -140,7 +234,23 @@ pub struct Invoke<'a> {
     pub invoker_opt: Option<Expr<'a>>,
     pub name: Span<'a>,
     pub args: Vec<Expr<'a>>,
-    pub method_def: Option<* const Method<'a>>
+    pub method_def: Option<* const Method<'a>>,
+    pub static_invoker_opt: Option<Span<'a>>,
+}
+
+impl <'a> Invoke<'a> {
+    // `panic(message)` is built in unless a method named panic is in scope, so, once analysed, it has no definition.
+    pub fn is_panic(&self) -> bool {
+        self.name.fragment == "panic" && self.method_def.is_none() && self.invoker_opt.is_none() && self.static_invoker_opt.is_none()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LogicalOperation<'a> {
+    pub left: Expr<'a>,
+    pub operator: Span<'a>, // Either `&&` or `||`. The right side is only evaluated when it decides the result.
+    pub right: Expr<'a>,
+    pub class_def: Option<* const Class<'a>>
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -150,12 +260,25 @@ pub struct MemberAccess<'a> {
     pub param_def: Option<* const Param<'a>>
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct MemberAssignment<'a> {
+    pub member: MemberAccess<'a>,
+    pub expr: Box<Expr<'a>>,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct NewInstance<'a> {
     pub name_opt: Option<Span<'a>>,
     pub args: Vec<Expr<'a>>,
-    // TODO(tanin): this should refer to a constructor
-    pub class_def: Option<* const Class<'a>>
+    pub class_def: Option<* const Class<'a>>,
+    // None refers to the primary constructor, which is defined by the class params.
+    pub constructor_def: Option<* const Method<'a>>
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SelfInstance<'a> {
+    pub span: Option<Span<'a>>, // None refers to the implicit receiver of a bare instance method call.
+    pub param_def: Option<* const Param<'a>>
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -164,23 +287,58 @@ pub struct LiteralString<'a> {
     pub instance: Option<Box<NewInstance<'a>>>
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct Interpolation<'a> {
+    pub span: Span<'a>,
+    pub parts: Vec<InterpolationPart<'a>>,
+    pub class_def: Option<* const Class<'a>>
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum InterpolationPart<'a> {
+    Text(LiteralString<'a>), // The span of the text excludes the quotes.
+    Expr(Expr<'a>),
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Int<'a> {
     pub span: Span<'a>,
     pub instance: Option<Box<NewInstance<'a>>>
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct Float<'a> {
+    pub span: Span<'a>,
+    pub instance: Option<Box<NewInstance<'a>>>
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Boolean<'a> {
+    pub span: Span<'a>,
+    pub instance: Option<Box<NewInstance<'a>>>
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Char<'a> {
     pub span: Span<'a>,
     pub instance: Option<Box<NewInstance<'a>>>
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct NativeBool {
+    pub value: bool
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct NativeChar {
     pub value: char
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct NativeFloat {
+    pub value: f64
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct NativeInt {
     pub value: i64
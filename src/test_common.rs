@@ -38,10 +38,12 @@ pub fn make_int_instance<'def>(value: i64, root: &index::tree::Root<'def>) -> Ne
                 args: vec![
                     Expr::NativeInt(Box::new(NativeInt { value }))
                 ],
-                class_def: Some(root.find_class("Native__Int"))
+                class_def: Some(root.find_class("Native__Int")),
+                constructor_def: None
             })),
         ],
-        class_def: Some(root.find_class("Int"))
+        class_def: Some(root.find_class("Int")),
+        constructor_def: None
     }
 }
 
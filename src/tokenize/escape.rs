@@ -0,0 +1,102 @@
+// Unescapes the content of a string literal, which excludes the quotes.
+// On failure, returns the byte offset of the invalid escape sequence within `raw` and the reason.
+pub fn unescape(raw: &str) -> Result<String, (usize, String)> {
+    let mut result = String::with_capacity(raw.len());
+    let mut chars = raw.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        let escaped = match chars.next() {
+            Some((_, e)) => e,
+            None => return Err((index, "The escape sequence is incomplete".to_string())),
+        };
+
+        result.push(match escaped {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            '#' => '#',
+            'x' => {
+                let mut hex = String::new();
+                for _ in 0..2 {
+                    match chars.next() {
+                        Some((_, h)) if h.is_ascii_hexdigit() => hex.push(h),
+                        _ => return Err((index, "Expect 2 hex digits after `\\x`".to_string())),
+                    }
+                }
+
+                let value = u8::from_str_radix(&hex, 16).unwrap();
+                if value > 0x7F {
+                    return Err((index, format!("\\x{} is out of the ASCII range. Use \\u{{...}} instead", hex)));
+                }
+                value as char
+            },
+            'u' => {
+                match chars.next() {
+                    Some((_, '{')) => (),
+                    _ => return Err((index, "Expect `{` after `\\u`".to_string())),
+                }
+
+                let mut hex = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '}')) => break,
+                        Some((_, h)) if h.is_ascii_hexdigit() && hex.len() < 6 => hex.push(h),
+                        _ => return Err((index, "Expect 1 to 6 hex digits followed by `}` after `\\u{`".to_string())),
+                    }
+                }
+
+                if hex.is_empty() {
+                    return Err((index, "Expect 1 to 6 hex digits followed by `}` after `\\u{`".to_string()));
+                }
+
+                match std::char::from_u32(u32::from_str_radix(&hex, 16).unwrap()) {
+                    Some(c) => c,
+                    None => return Err((index, format!("\\u{{{}}} isn't a valid unicode scalar value", hex))),
+                }
+            },
+            other => return Err((index, format!("Unknown escape sequence \\{}", other))),
+        });
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokenize::escape::unescape;
+
+    #[test]
+    fn test_simple() {
+        assert_eq!(unescape(r#"a\n\t\r\0\\\"\'\#{b}"#), Ok("a\n\t\r\0\\\"'#{b}".to_string()));
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(unescape(r#"\x41\x7f"#), Ok("A\x7f".to_string()));
+    }
+
+    #[test]
+    fn test_unicode() {
+        assert_eq!(unescape(r#"打包\u{1F600}\u{e9}"#), Ok("打包😀é".to_string()));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(unescape(r#"ab\q"#), Err((2, "Unknown escape sequence \\q".to_string())));
+        assert_eq!(unescape(r#"\u{}"#).is_err(), true);
+        assert_eq!(unescape(r#"\u{D800}"#).is_err(), true);
+        assert_eq!(unescape(r#"\u{1234567}"#).is_err(), true);
+        assert_eq!(unescape("a\\").is_err(), true);
+        assert_eq!(unescape(r#"\x4"#).is_err(), true);
+        assert_eq!(unescape(r#"\x80"#).is_err(), true);
+    }
+}
@@ -1,15 +1,12 @@
-use std::io::Write;
-use std::ops::Index;
-use std::{io, slice};
 use tokenize::combinator::{
-    concat, take, take_bit, take_hex_number, take_number, take_one_if_case_insensitive, take_while,
+    consumed, take, take_bit, take_hex_number, take_number, take_one_if_case_insensitive, take_while,
 };
-use tokenize::span::CharAt;
 use tokenize::span::Span;
 use tokenize::token::Token;
 use LilitFile;
 
 pub mod combinator;
+pub mod escape;
 pub mod span;
 pub mod token;
 
@@ -17,26 +14,12 @@ pub fn apply<'def>(
     content: &'def str,
     file: *const LilitFile<'def>,
 ) -> Result<Vec<Token<'def>>, Span<'def>> {
-    let mut input = Span {
+    apply_span(Span {
         line: 1,
         col: 1,
         fragment: content,
         file,
-    };
-    let mut tokens = vec![];
-
-    while input.fragment.len() > 0 {
-        let (next_input, token_opt) = tokenize(input)?;
-        input = next_input;
-
-        match token_opt {
-            Some(Token::Comment(_)) => (),
-            Some(token) => tokens.push(token),
-            None => (),
-        };
-    }
-
-    Ok(tokens)
+    })
 }
 
 fn tokenize<'def>(input: Span<'def>) -> Result<(Span<'def>, Option<Token<'def>>), Span<'def>> {
@@ -46,9 +29,13 @@ fn tokenize<'def>(input: Span<'def>) -> Result<(Span<'def>, Option<Token<'def>>)
         return Ok((input, None));
     }
 
-    if let Ok((input, token)) = oneline_comment(input) {
+    if input.fragment.starts_with("/*") {
+        let (input, token) = block_comment(input)?;
+        Ok((input, Some(token)))
+    } else if let Ok((input, token)) = oneline_comment(input) {
         Ok((input, Some(token)))
-    } else if let Ok((input, token)) = string(input) {
+    } else if input.fragment.starts_with('"') {
+        let (input, token) = string(input)?;
         Ok((input, Some(token)))
     } else if let Ok((input, token)) = literal_char(input) {
         Ok((input, Some(token)))
@@ -67,163 +54,243 @@ fn tokenize<'def>(input: Span<'def>) -> Result<(Span<'def>, Option<Token<'def>>)
     }
 }
 
-fn is_whitespace(index: usize, s: &str) -> bool {
-    let c = s.char_at(index);
-    c == ' ' || c == '\t' || c == '\r' || c == '\n' || c == 12 as char // form feed
-}
-
 fn skip_space(input: Span) -> Span {
-    let (_, input) = take_while(is_whitespace, input);
+    let (_, input) = take_while(|_, c| c.is_whitespace(), input);
     input
 }
 
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum StringPart<'a> {
+    Text(Span<'a>),
+    Expr(Span<'a>),
+}
+
 fn string(input: Span) -> Result<(Span, Token), Span> {
-    if input.fragment.char_at(0) != '"' {
-        return Err(input);
+    let (after, string, parts) = scan_string(input)?;
+
+    if parts.iter().any(|p| if let StringPart::Expr(_) = p { true } else { false }) {
+        Ok((after, Token::Interpolation(string)))
+    } else {
+        Ok((after, Token::String(string)))
     }
+}
 
-    let mut escaped = false;
-    let mut size = 1;
+// Splits an interpolated string into its raw texts and the spans of the interpolated expressions.
+// The texts exclude the quotes, and the expressions exclude `#{` and `}`. Empty texts are omitted.
+pub fn split_interpolation(input: Span) -> Result<Vec<StringPart>, Span> {
+    let (_, _, parts) = scan_string(input)?;
+    Ok(parts)
+}
 
-    for index in 1..input.fragment.len() {
-        let c = input.fragment.char_at(index);
+// Tokenizes the span of an interpolated expression, which is produced by `split_interpolation`.
+pub fn apply_span(input: Span) -> Result<Vec<Token>, Span> {
+    scan(input, false)
+}
 
-        size += 1;
+// The parser doesn't expect comments, so only the formatter, which preserves them, keeps them.
+pub fn apply_with_comments<'def>(
+    content: &'def str,
+    file: *const LilitFile<'def>,
+) -> Result<Vec<Token<'def>>, Span<'def>> {
+    scan(
+        Span {
+            line: 1,
+            col: 1,
+            fragment: content,
+            file,
+        },
+        true
+    )
+}
 
-        if escaped {
-            escaped = false;
-            continue;
-        }
+fn scan(mut input: Span, keep_comments: bool) -> Result<Vec<Token>, Span> {
+    let mut tokens = vec![];
 
-        if c == '"' {
-            break;
+    while input.fragment.len() > 0 {
+        let (next_input, token_opt) = tokenize(input)?;
+        input = next_input;
+
+        match token_opt {
+            Some(Token::Comment(_)) if !keep_comments => (),
+            Some(token) => tokens.push(token),
+            None => (),
+        };
+    }
+
+    Ok(tokens)
+}
+
+fn scan_string(original: Span) -> Result<(Span, Span, Vec<StringPart>), Span> {
+    if !original.fragment.starts_with('"') {
+        return Err(original);
+    }
+
+    let mut parts = vec![];
+    let (_, mut input) = take(1, original);
+    let mut text_start = input;
+
+    loop {
+        if input.fragment.is_empty() {
+            return Err(original);
         }
 
-        if c == '\\' {
-            escaped = true;
+        if input.fragment.starts_with('"') {
+            push_text(text_start, input, &mut parts);
+            let (_, after) = take(1, input);
+            return Ok((after, consumed(original, after), parts));
+        } else if input.fragment.starts_with('\\') {
+            let (escape, after) = take(2, input);
+            if escape.fragment.is_empty() {
+                return Err(original);
+            }
+            input = after;
+        } else if input.fragment.starts_with("#{") {
+            push_text(text_start, input, &mut parts);
+            let (_, expr_start) = take(2, input);
+            let (expr, after) = scan_interpolated_expr(expr_start)?;
+            parts.push(StringPart::Expr(expr));
+            input = after;
+            text_start = input;
+        } else {
+            input = take(1, input).1;
         }
     }
+}
 
-    let (string, after) = take(size, input);
+fn push_text<'a>(start: Span<'a>, end: Span<'a>, parts: &mut Vec<StringPart<'a>>) {
+    let text = consumed(start, end);
 
-    Ok((after, Token::String(string)))
+    if !text.fragment.is_empty() {
+        parts.push(StringPart::Text(text));
+    }
 }
 
-fn literal_char(input: Span) -> Result<(Span, Token), Span> {
-    if input.fragment.char_at(0) == '\'' {
-    } else {
-        return Err(input);
+// Returns the span of the expression and the input after the closing `}`.
+fn scan_interpolated_expr(original: Span) -> Result<(Span, Span), Span> {
+    let mut input = original;
+    let mut depth = 0;
+
+    loop {
+        let (next_input, token_opt) = tokenize(input)?;
+
+        match token_opt {
+            None => return Err(original),
+            Some(Token::Symbol(s)) if s.fragment == "{" => depth += 1,
+            Some(Token::Symbol(s)) if s.fragment == "}" => {
+                if depth == 0 {
+                    let with_brace = consumed(original, next_input);
+                    let expr = Span { fragment: &with_brace.fragment[..(with_brace.fragment.len() - 1)], ..with_brace };
+
+                    if expr.fragment.trim().is_empty() {
+                        return Err(s);
+                    }
+
+                    return Ok((expr, next_input));
+                }
+                depth -= 1;
+            },
+            _ => (),
+        }
+
+        input = next_input;
+    }
+}
+
+fn literal_char(original: Span) -> Result<(Span, Token), Span> {
+    if !original.fragment.starts_with('\'') {
+        return Err(original);
     }
 
-    let (literal_char, after) = take_while(
-        |index, s| {
-            let end_cond =
-                index >= 2 && s.char_at(index - 2) != '\\' && s.char_at(index - 1) == '\'';
-            let end_cond_2 = index >= 3
-                && s.char_at(index - 3) == '\\'
-                && s.char_at(index - 2) == '\\'
-                && s.char_at(index - 1) == '\'';
+    let (_, mut input) = take(1, original);
 
-            !(end_cond || end_cond_2)
-        },
-        input,
-    );
+    loop {
+        if input.fragment.starts_with('\\') {
+            let (_, after) = take(1, input);
+            input = take(1, after).1;
+        } else if input.fragment.starts_with('\'') || input.fragment.is_empty() {
+            input = take(1, input).1;
+            break;
+        } else {
+            input = take(1, input).1;
+        }
+    }
 
-    Ok((after, Token::Char(literal_char)))
+    Ok((input, Token::Char(consumed(original, input))))
 }
 
-fn hex_p<'a>(num: Span<'a>, original: Span<'a>) -> Result<(Span<'a>, Token<'a>), Span<'a>> {
+fn hex_p<'a>(start: Span<'a>, original: Span<'a>) -> Result<(Span<'a>, Token<'a>), Span<'a>> {
     let (must_be_p, input) = take_one_if_case_insensitive("P", original);
 
     if must_be_p.fragment.is_empty() {
         return Err(original);
     }
 
-    let (maybe_sign, input) = take_one_if_case_insensitive("-+", input);
+    let (_, input) = take_one_if_case_insensitive("-+", input);
     let (exponent, input) = take_number(input);
 
     if exponent.fragment.is_empty() {
         return Err(input);
     }
 
-    let num = concat(&[num, must_be_p, maybe_sign, exponent]);
-
-    Ok((input, Token::Float(num)))
+    Ok((input, Token::Float(consumed(start, input))))
 }
 
-fn hex_decimal<'a>(num: Span<'a>, original: Span<'a>) -> Result<(Span<'a>, Token<'a>), Span<'a>> {
+fn hex_decimal<'a>(start: Span<'a>, num: Span<'a>, original: Span<'a>) -> Result<(Span<'a>, Token<'a>), Span<'a>> {
     let (maybe_p_or_dot, input) = take_one_if_case_insensitive("P.", original);
 
     if maybe_p_or_dot.fragment.is_empty() {
-        return Ok((input, Token::Int(num)));
+        return Ok((input, Token::Int(consumed(start, input))));
     }
 
-    if maybe_p_or_dot.fragment.char_at(0) != '.' {
-        return hex_p(num, original);
+    if maybe_p_or_dot.fragment != "." {
+        return hex_p(start, original);
     }
 
-    let must_be_dot = maybe_p_or_dot;
-    assert_eq!(must_be_dot.fragment, ".");
-
     let (decimal, input) = take_hex_number(input);
 
     if num.fragment.is_empty() && decimal.fragment.is_empty() {
         return Err(original);
     }
 
-    let num = concat(&[num, must_be_dot, decimal]);
-
-    hex_p(num, input)
+    hex_p(start, input)
 }
 
 fn hex(original: Span) -> Result<(Span, Token), Span> {
-    if original.fragment.len() >= 2
-        && original.fragment.char_at(0) == '0'
-        && original.fragment.char_at(1).to_ascii_uppercase() == 'X'
-    {
-    } else {
+    if !(original.fragment.starts_with("0x") || original.fragment.starts_with("0X")) {
         return Err(original);
     }
 
-    let (prefix, input) = take(2, original);
-    let (hex, input) = take_hex_number(input);
+    let (_, input) = take(2, original);
+    let (num, input) = take_hex_number(input);
 
-    let num = concat(&[prefix, hex]);
-
-    hex_decimal(num, input)
+    hex_decimal(original, num, input)
 }
 
 fn bit(original: Span) -> Result<(Span, Token), Span> {
-    if original.fragment.len() >= 2
-        && original.fragment.char_at(0) == '0'
-        && original.fragment.char_at(1).to_ascii_uppercase() == 'B'
-    {
-    } else {
+    if !(original.fragment.starts_with("0b") || original.fragment.starts_with("0B")) {
         return Err(original);
     }
 
-    let (prefix, input) = take(2, original);
-    let (bits, input) = take_bit(input);
-
-    let num = concat(&[prefix, bits]);
+    let (_, input) = take(2, original);
+    let (_, input) = take_bit(input);
 
-    Ok((input, Token::Int(num)))
+    Ok((input, Token::Int(consumed(original, input))))
 }
 
-fn is_identifier(index: usize, s: &str) -> bool {
-    let c = s.char_at(index);
-    c >= '0' && c <= '9' || c >= 'a' && c <= 'z' || c >= 'A' && c <= 'Z' || c == '_' || c == '$'
+// An identifier is made of Unicode letters and digits, e.g. Thai or Ärger, so punctuation such as “, → or … ends it.
+fn is_identifier(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
 }
 
 fn is_keyword(s: &str) -> bool {
     match s {
-        "class" | "def" | "static" | "end" | "while" => true,
+        "class" | "def" | "static" | "end" | "while" | "var" | "val" | "self" | "true" | "false" | "using" => true,
         _ => false,
     }
 }
 
 fn keyword_or_identifier(original: Span) -> Result<(Span, Token), Span> {
-    let (ident, input) = take_while(is_identifier, original);
+    let (ident, input) = take_while(|_, c| is_identifier(c), original);
 
     if ident.fragment.is_empty() {
         return Err(original);
@@ -231,7 +298,7 @@ fn keyword_or_identifier(original: Span) -> Result<(Span, Token), Span> {
 
     if is_keyword(ident.fragment) {
         Ok((input, Token::Keyword(ident)))
-    } else if ident.fragment.char_at(0) == ident.fragment.char_at(0).to_uppercase().to_string().char_at(0) {
+    } else if ident.fragment.chars().next().unwrap().is_uppercase() {
         Ok((input, Token::Capitalize(ident)))
     } else {
         Ok((input, Token::Identifier(ident)))
@@ -247,7 +314,8 @@ fn symbol(input: Span) -> Result<(Span, Token), Span> {
 }
 
 fn float_e<'a>(
-    num: Span<'a>,
+    start: Span<'a>,
+    is_num_empty: bool,
     original: Span<'a>,
     include_dot: bool,
 ) -> Result<(Span<'a>, Token<'a>), Span<'a>> {
@@ -255,64 +323,45 @@ fn float_e<'a>(
 
     if maybe_e.fragment.is_empty() {
         if include_dot {
-            return Ok((original, Token::Float(num)))
+            return Ok((original, Token::Float(consumed(start, original))))
         } else {
             return Err(input);
         }
     }
 
-    if num.fragment.is_empty() {
+    if is_num_empty {
         return Err(input);
     }
 
-    let (maybe_operator, input) = take_one_if_case_insensitive("+-", input);
-    let (second_number, input) = take_number(input);
-
-    let num = concat(&[num, maybe_e, maybe_operator, second_number]);
+    let (_, input) = take_one_if_case_insensitive("+-", input);
+    let (_, input) = take_number(input);
 
-    Ok((input, Token::Float(num)))
+    Ok((input, Token::Float(consumed(start, input))))
 }
 
 fn float_dot<'a>(
+    start: Span<'a>,
     first_number: Span<'a>,
     original: Span<'a>,
 ) -> Result<(Span<'a>, Token<'a>), Span<'a>> {
-    let (symbol, input) = take(1, original);
-    let last_char = if !symbol.fragment.is_empty() {
-        symbol.fragment.char_at(0).to_ascii_uppercase()
-    } else {
-        ' ' // anything that is not a dot.
-    };
-
-    if last_char != '.' {
-        return float_e(first_number, original, false);
+    if !original.fragment.starts_with('.') {
+        return float_e(start, first_number.fragment.is_empty(), original, false);
     }
 
+    let (_, input) = take(1, original);
     let (second_number, input) = take_number(input);
 
     if second_number.fragment.is_empty() {
         return Err(original);
     }
 
-    let num = Span {
-        line: first_number.line,
-        col: first_number.col,
-        fragment: unsafe {
-            std::str::from_utf8_unchecked(slice::from_raw_parts(
-                first_number.fragment.as_ptr(),
-                first_number.fragment.len() + symbol.fragment.len() + second_number.fragment.len(),
-            ))
-        },
-        file: first_number.file,
-    };
-
-    float_e(num, input, true)
+    float_e(start, false, input, true)
 }
 
 fn int_or_float(original: Span) -> Result<(Span, Token), Span> {
     let (number, input) = take_number(original);
 
-    if let Ok(ok) = float_dot(number, input) {
+    if let Ok(ok) = float_dot(original, number, input) {
         return Ok(ok);
     }
 
@@ -324,17 +373,46 @@ fn int_or_float(original: Span) -> Result<(Span, Token), Span> {
 }
 
 fn oneline_comment(input: Span) -> Result<(Span, Token), Span> {
-    if input.fragment.len() >= 2
-        && input.fragment.char_at(0) == '/'
-        && input.fragment.char_at(1) == '/'
-    {
-    } else {
+    if !input.fragment.starts_with("//") {
         return Err(input);
     }
 
-    let (comment, after) = take_while(|index, s| s.char_at(index) != '\n', input);
+    let (comment, after) = take_while(|_, c| c != '\n', input);
 
-    Ok((after, Token::Comment(comment)))
+    // `////` is a regular comment, e.g. a separator line.
+    if comment.fragment.starts_with("///") && !comment.fragment.starts_with("////") {
+        Ok((after, Token::DocComment(comment)))
+    } else {
+        Ok((after, Token::Comment(comment)))
+    }
+}
+
+// Block comments can be nested, so a block that contains `*/` can be commented out.
+fn block_comment(original: Span) -> Result<(Span, Token), Span> {
+    if !original.fragment.starts_with("/*") {
+        return Err(original);
+    }
+
+    let (_, mut input) = take(2, original);
+    let mut depth = 1;
+
+    while depth > 0 {
+        if input.fragment.is_empty() {
+            return Err(original);
+        }
+
+        if input.fragment.starts_with("/*") {
+            depth += 1;
+            input = take(2, input).1;
+        } else if input.fragment.starts_with("*/") {
+            depth -= 1;
+            input = take(2, input).1;
+        } else {
+            input = take(1, input).1;
+        }
+    }
+
+    Ok((input, Token::Comment(consumed(original, input))))
 }
 
 #[cfg(test)]
@@ -352,6 +430,50 @@ mod tests {
         assert_eq!(apply("// test"), Ok(vec![]))
     }
 
+    #[test]
+    fn test_with_comments() {
+        assert_eq!(
+            super::apply_with_comments("a // test", std::ptr::null()),
+            Ok(vec![Token::Identifier(span(1, 1, "a")), Token::Comment(span(1, 3, "// test"))])
+        )
+    }
+
+    #[test]
+    fn test_block_comment() {
+        assert_eq!(
+            apply(
+                r#"
+a /* one /* two */
+  still */ b
+"#
+                    .trim()
+            ),
+            Ok(vec![
+                Token::Identifier(span(1, 1, "a")),
+                Token::Identifier(span(2, 12, "b")),
+            ])
+        );
+        assert_eq!(apply("/* /* */"), Err(span(1, 1, "/* /* */")));
+    }
+
+    #[test]
+    fn test_doc_comment() {
+        assert_eq!(
+            apply(
+                r#"
+/// Doc
+//// Not a doc
+class
+"#
+                    .trim()
+            ),
+            Ok(vec![
+                Token::DocComment(span(1, 1, "/// Doc")),
+                Token::Keyword(span(3, 1, "class")),
+            ])
+        )
+    }
+
     #[test]
     fn test_unicode() {
         assert_eq!(
@@ -365,6 +487,49 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_unicode_identifier() {
+        assert_eq!(
+            apply(
+                r#"
+// ความคิดเห็น
+def ทดสอบ(): Ärger
+  "สวัสดี" ผล.x
+end
+"#
+                    .trim()
+            ),
+            Ok(vec![
+                Token::Keyword(span(2, 1, "def")),
+                Token::Identifier(span(2, 5, "ทดสอบ")),
+                Token::Symbol(span(2, 10, "(")),
+                Token::Symbol(span(2, 11, ")")),
+                Token::Symbol(span(2, 12, ":")),
+                Token::Capitalize(span(2, 14, "Ärger")),
+                Token::String(span(3, 3, "\"สวัสดี\"")),
+                Token::Identifier(span(3, 12, "ผล")),
+                Token::Symbol(span(3, 14, ".")),
+                Token::Identifier(span(3, 15, "x")),
+                Token::Keyword(span(4, 1, "end")),
+            ])
+        )
+    }
+
+    #[test]
+    fn test_unicode_punctuation() {
+        assert_eq!(
+            apply("ab“c a→b"),
+            Ok(vec![
+                Token::Identifier(span(1, 1, "ab")),
+                Token::Symbol(span(1, 3, "“")),
+                Token::Identifier(span(1, 4, "c")),
+                Token::Identifier(span(1, 6, "a")),
+                Token::Symbol(span(1, 7, "→")),
+                Token::Identifier(span(1, 8, "b")),
+            ])
+        );
+    }
+
     #[test]
     fn test_empty_string() {
         assert_eq!(
@@ -445,6 +610,42 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_interpolation() {
+        assert_eq!(
+            apply(
+                r#"
+"a #{b("}")} c" +
+"#
+                    .trim()
+            ),
+            Ok(vec![
+                Token::Interpolation(span(1, 1, "\"a #{b(\"}\")} c\"")),
+                Token::Symbol(span(1, 17, "+"))
+            ])
+        )
+    }
+
+    #[test]
+    fn test_split_interpolation() {
+        let tokens = apply(r#""a #{b({c})}#{d}\#{e}""#).unwrap();
+        assert_eq!(
+            super::split_interpolation(tokens[0].span()),
+            Ok(vec![
+                super::StringPart::Text(span(1, 2, "a ")),
+                super::StringPart::Expr(span(1, 6, "b({c})")),
+                super::StringPart::Expr(span(1, 15, "d")),
+                super::StringPart::Text(span(1, 17, "\\#{e}")),
+            ])
+        )
+    }
+
+    #[test]
+    fn test_unterminated_interpolation() {
+        assert_eq!(apply(r#""a #{b""#).is_err(), true);
+        assert_eq!(apply(r#""a #{}""#).is_err(), true);
+    }
+
     #[test]
     fn test_empty_char() {
         assert_eq!(
@@ -3,21 +3,28 @@ use LilitFile;
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub struct Span<'a> {
     pub line: usize,
-    pub col: usize,
+    pub col: usize, // The column is counted in chars, not bytes.
     pub fragment: &'a str,
     pub file: *const LilitFile<'a>,
 }
 
+impl <'a> Span<'a> {
+    // Returns the line and the column right after the last char of the fragment.
+    pub fn get_end(&self) -> (usize, usize) {
+        match self.fragment.rfind('\n') {
+            Some(index) => (self.line + self.fragment.matches('\n').count(), self.fragment[index + 1..].chars().count() + 1),
+            None => (self.line, self.col + self.fragment.chars().count()),
+        }
+    }
+}
+
 pub trait CharAt {
     fn char_at(&self, i: usize) -> char;
 }
 
 impl CharAt for str {
+    // The index is counted in chars, not bytes.
     fn char_at(&self, i: usize) -> char {
-        raw_char_at(i, self)
+        self.chars().nth(i).unwrap()
     }
 }
-
-fn raw_char_at(index: usize, s: &str) -> char {
-    unsafe { *s.as_ptr().add(index) as char }
-}
\ No newline at end of file
@@ -5,8 +5,10 @@ pub enum Token<'a> {
     Capitalize(Span<'a>),
     Char(Span<'a>),
     Comment(Span<'a>),
+    DocComment(Span<'a>),
     Float(Span<'a>),
     Int(Span<'a>),
+    Interpolation(Span<'a>),
     String(Span<'a>),
     Symbol(Span<'a>),
     Identifier(Span<'a>),
@@ -19,9 +21,11 @@ impl<'a> Token<'a> {
             Token::Capitalize(s) => s,
             Token::Char(s) => s,
             Token::Comment(s) => s,
+            Token::DocComment(s) => s,
             Token::Float(s) => s,
             Token::Identifier(s) => s,
             Token::Int(s) => s,
+            Token::Interpolation(s) => s,
             Token::Keyword(s) => s,
             Token::String(s) => s,
             Token::Symbol(s) => s,
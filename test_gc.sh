@@ -0,0 +1,38 @@
+#!/bin/bash
+# Builds the example and checks that the runtime reads the LILIT_GC_* env vars.
+EXAMPLE=${1:-examples/instance_method.lilit}
+
+clang -S -emit-llvm -I ~/projects/bdwgc/include/ -o native/lib.ll native/lib.c \
+  && llc-6.0 -filetype=obj native/lib.ll \
+  && cargo run -- $EXAMPLE \
+  && cc native/lib.o output/main.o ~/projects/bdwgc/.libs/libgc.so -I ~/projects/bdwgc/include/ -o output/main_gc -no-pie -rdynamic \
+  || exit 1
+
+./output/main_gc > output/gc_output.txt 2> output/gc.txt
+exit_code=$?
+if grep -q "^GC: " output/gc.txt; then
+  echo "The stats are printed without LILIT_GC_STATS=1"
+  exit 1
+fi
+
+LILIT_GC_STATS=1 ./output/main_gc 2> output/gc.txt
+if ! grep -q "^GC: " output/gc.txt; then
+  echo "LILIT_GC_STATS=1 doesn't print the stats"
+  exit 1
+fi
+
+LILIT_GC_STATS=1 LILIT_GC_INITIAL_HEAP_SIZE=67108864 ./output/main_gc 2> output/gc.txt
+heap_size=$(sed -n 's/^GC: .*, \([0-9]*\) bytes in the heap.*/\1/p' output/gc.txt)
+if [ -z "$heap_size" ] || [ "$heap_size" -lt 67108864 ]; then
+  echo "LILIT_GC_INITIAL_HEAP_SIZE=67108864 doesn't grow the heap: $heap_size bytes"
+  exit 1
+fi
+
+# Collecting in small steps isn't visible from the outside, so the program only has to behave the same.
+LILIT_GC_INCREMENTAL=1 ./output/main_gc > output/gc_incremental.txt
+if [ $? -ne $exit_code ] || ! diff output/gc_output.txt output/gc_incremental.txt; then
+  echo "LILIT_GC_INCREMENTAL=1 behaves differently"
+  exit 1
+fi
+
+echo "The GC reads every LILIT_GC_* env var"
@@ -0,0 +1,23 @@
+#!/bin/bash
+# Builds the example at every optimization level and checks that the output and the exit code are the same as -O0's.
+EXAMPLE=${1:-examples/printf.lilit}
+
+clang -S -emit-llvm -I ~/projects/bdwgc/include/ -o native/lib.ll native/lib.c \
+  && llc-6.0 -filetype=obj native/lib.ll \
+  || exit 1
+
+for level in O0 O1 O2 O3 Os; do
+  cargo run -- -$level $EXAMPLE \
+    && cc native/lib.o output/main.o ~/projects/bdwgc/.libs/libgc.so -I ~/projects/bdwgc/include/ -o output/main_$level -no-pie -rdynamic \
+    || exit 1
+
+  ./output/main_$level > output/$level.txt
+  echo "Exit code: $?" >> output/$level.txt
+
+  if ! diff output/O0.txt output/$level.txt; then
+    echo "-$level behaves differently from -O0"
+    exit 1
+  fi
+done
+
+echo "Every level behaves the same"